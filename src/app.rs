@@ -0,0 +1,869 @@
+use std::fs::File;
+use std::io::{self, stdout, BufWriter, Stdout, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::Hide;
+use crossterm::event::{poll, read, Event, KeyCode, MouseButton, MouseEventKind};
+use crossterm::terminal;
+use crossterm::QueueableCommand;
+
+use crate::buffer::{Buffer, Heatmap, RenderMode};
+use crate::cast::Recorder;
+use crate::color_depth::ColorDepth;
+use crate::grid::Grid;
+use crate::hud::{self, FrameStats, Hud};
+use crate::maze::{Change, GeneratorKind, Progress, Solution, SolverKind};
+use crate::minimap::Minimap;
+use crate::point::Point;
+use crate::race::{Race, Standings};
+use crate::screen_state::{self, ScreenState};
+use crate::space::Space;
+use crate::theme::{Theme, ThemeKind};
+use crate::timeline::Timeline;
+use crate::viewport::Viewport;
+
+/// The interactive app: builds mazes to fit the terminal and solves them on
+/// screen while handling input, one after another until the user quits. A
+/// solved maze, or a finished race, stays up until the user moves on, unless
+/// [`App::loop_mazes`] is set.
+#[derive(Debug, Clone)]
+pub struct App {
+    pub generator: GeneratorKind,
+    /// Grid size, or what fits the terminal when `None`.
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub solver: SolverKind,
+    /// Race these solvers side by side instead of running `solver`.
+    pub race: Vec<SolverKind>,
+    pub render: RenderMode,
+    pub heatmap: bool,
+    /// Frames drawn per second at most.
+    pub fps: u32,
+    /// Show how long each frame took to draw and how big it was.
+    pub frame_stats: bool,
+    /// Also record the session to this asciicast file.
+    pub record: Option<PathBuf>,
+    pub theme: ThemeKind,
+    /// A theme file to start with instead of `theme`.
+    pub theme_file: Option<PathBuf>,
+    /// Detected from the environment when `None`.
+    pub color_depth: Option<ColorDepth>,
+    /// Move on to a new maze [`RESULT_TIME`] after each one is solved rather
+    /// than waiting for a key.
+    pub loop_mazes: bool,
+}
+
+impl App {
+    /// Runs until the user quits, starting with the maze for `seed`. Each new
+    /// maze after it takes the next seed up, the way a headless batch does, so
+    /// the whole run can be had again from the first seed. Returns the seed of
+    /// the last maze shown.
+    pub fn run(&self, mut seed: u64) -> io::Result<u64> {
+        let mut themes: Vec<Theme> = ThemeKind::ALL.iter().map(ThemeKind::theme).collect();
+        let mut theme = ThemeKind::ALL
+            .iter()
+            .position(|kind| *kind == self.theme)
+            .unwrap_or(0);
+        if let Some(path) = &self.theme_file {
+            themes.push(Theme::load(path)?);
+            theme = themes.len() - 1;
+        }
+        let depth = self.color_depth.unwrap_or_else(ColorDepth::detect);
+        let output = match &self.record {
+            Some(path) => {
+                let (width, height) = terminal::size()?;
+                let cast = BufWriter::new(File::create(path)?);
+                let mut recorder = Recorder::new(stdout(), cast, width, height)?;
+                // The player shows a cursor unless told otherwise.
+                recorder.queue(Hide)?;
+                Output::Recording(recorder)
+            }
+            None => Output::Terminal(stdout()),
+        };
+        let mut view = View {
+            render: self.render,
+            depth,
+            heatmap: self.heatmap,
+            frame_time: Duration::from_secs(1) / self.fps,
+            frame_stats: self.frame_stats,
+            hold: self.loop_mazes.then_some(RESULT_TIME),
+            themes: Arc::new(themes),
+            theme: Arc::new(AtomicUsize::new(theme)),
+            changes: Arc::default(),
+            output: Arc::new(Mutex::new(output)),
+        };
+        if depth == ColorDepth::NoColor {
+            // Without colour only glyphs tell spaces apart, and half blocks and the
+            // heatmap are nothing but colour.
+            let theme = match &self.theme_file {
+                Some(_) => view.themes[theme].without_color(),
+                None => ThemeKind::Ascii.theme(),
+            };
+            view.themes = Arc::new(vec![theme]);
+            view.theme = Arc::new(AtomicUsize::new(0));
+            view.render = RenderMode::Full;
+            view.heatmap = false;
+        }
+
+        let _screen_state = ScreenState::enable()?;
+
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            ScreenState::disable();
+            default_hook(info);
+        }));
+
+        loop {
+            let (screen_width, screen_height) = terminal::size()?;
+            let size = (screen_width as usize, screen_height as usize);
+            let next = match self.race.is_empty() {
+                true => self.run_maze(&view, size, seed)?,
+                false => self.run_race(&view, size, seed)?,
+            };
+            match next {
+                Next::Quit => break,
+                // Regenerating keeps the seed, so the same maze is rebuilt to fit
+                // the terminal as it is now.
+                Next::Regenerate => {}
+                Next::NewMaze => seed = seed.wrapping_add(1),
+            }
+        }
+        Ok(seed)
+    }
+
+    /// Builds one maze, then solves it on screen while handling input.
+    fn run_maze(&self, view: &View, size: (usize, usize), seed: u64) -> io::Result<Next> {
+        // The bottom rows are reserved for the HUD. Grids bigger than the space
+        // left are scrolled and zoomed.
+        let (term_width, term_height) = (
+            size.0,
+            size.1.saturating_sub(view.hud_rows()) * view.render.rows_per_cell(),
+        );
+        let width = self.width.unwrap_or(term_width);
+        let height = self.height.unwrap_or(term_height);
+
+        let Some(maze) = self.generator.build(Grid::new(width, height), seed) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "grid is too small for a maze",
+            ));
+        };
+        let start = maze.start;
+        let hud = Hud {
+            generator: self.generator.name(),
+            solver: self.solver.name(),
+            seed,
+            width,
+            height,
+            notice: None,
+        };
+
+        let solver = self.solver;
+        let thread_grid = maze.grid.clone();
+        let progress = Arc::new(Progress::default());
+        // Subscribed before the solver starts, so the generator's changes followed
+        // by the solver's add up to the solver's grid.
+        let events = progress.subscribe();
+        let mut timeline = Timeline::new(Grid::new(width, height));
+        for &change in &maze.changes {
+            timeline.record(change);
+        }
+        let thread_progress = progress.clone();
+        let maze_thread = thread::spawn(move || {
+            solver.solve(
+                start,
+                thread_grid,
+                Duration::from_millis(1),
+                &thread_progress,
+            );
+        });
+
+        let writer_view = view.clone();
+        let screen = Screen {
+            size,
+            hud,
+            progress: progress.clone(),
+        };
+        let writer_thread = thread::spawn(move || {
+            let _ = writer_thread(maze_thread, timeline, events, writer_view, screen);
+        });
+        wait_for_writer(writer_thread, view, &[progress], seed)
+    }
+
+    /// Builds one maze and races [`App::race`] against each other on copies of
+    /// it, side by side.
+    fn run_race(&self, view: &View, size: (usize, usize), seed: u64) -> io::Result<Next> {
+        let lanes = self.race.len();
+        let pane_width = ((size.0 + 1) / lanes).saturating_sub(1);
+        let pane_height = size.1.saturating_sub(RACE_ROWS) * view.render.rows_per_cell();
+        let width = self.width.unwrap_or(pane_width);
+        let height = self.height.unwrap_or(pane_height);
+
+        let Some(maze) = self.generator.build(Grid::new(width, height), seed) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "grid is too small for a maze",
+            ));
+        };
+        let race = Race::new(&maze.grid.lock().unwrap(), &self.race);
+        // Every lane's changes come down one channel, tagged with the lane, so
+        // the writer can wait on all of them at once.
+        let (sender, events) = mpsc::channel();
+        for (i, lane) in race.lanes.iter().enumerate() {
+            let (changes, sender) = (lane.progress.subscribe(), sender.clone());
+            thread::spawn(move || {
+                for change in changes {
+                    let _ = sender.send((i, change));
+                }
+            });
+        }
+        let grids: Vec<_> = race
+            .lanes
+            .iter()
+            .map(|lane| lane.grid.lock().unwrap().clone())
+            .collect();
+        let solvers = race.start(maze.start, Duration::from_millis(1));
+        let progress: Vec<Arc<Progress>> = race
+            .lanes
+            .iter()
+            .map(|lane| lane.progress.clone())
+            .collect();
+
+        let writer_view = view.clone();
+        let hud = Hud {
+            generator: self.generator.name(),
+            solver: "race",
+            seed,
+            width,
+            height,
+            notice: None,
+        };
+        let writer_thread = thread::spawn(move || {
+            let _ = race_writer(race, solvers, grids, events, writer_view, size, hud);
+        });
+        wait_for_writer(writer_thread, view, &progress, seed)
+    }
+}
+
+/// What to do once a maze is finished with.
+enum Next {
+    Quit,
+    Regenerate,
+    NewMaze,
+}
+
+/// How the writer thread draws the grid. The theme index is shared with the
+/// input loop so it can be changed with a hotkey mid-run, and so is a queue
+/// of changes to the viewport the writer hasn't applied yet.
+#[derive(Clone)]
+struct View {
+    render: RenderMode,
+    depth: ColorDepth,
+    heatmap: bool,
+    /// The shortest time between frames.
+    frame_time: Duration,
+    frame_stats: bool,
+    /// How long a solved maze stays up before the writer moves on by itself,
+    /// or `None` to wait for the input loop to move on.
+    hold: Option<Duration>,
+    themes: Arc<Vec<Theme>>,
+    theme: Arc<AtomicUsize>,
+    changes: Arc<ViewChanges>,
+    output: Arc<Mutex<Output>>,
+}
+
+/// Where frames are written: the terminal, and with [`App::record`] a
+/// recording of it as well.
+enum Output {
+    Terminal(Stdout),
+    Recording(Recorder<Stdout, BufWriter<File>>),
+}
+
+impl Output {
+    /// Tells the recording, if there is one, that the terminal was resized.
+    fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        match self {
+            Output::Terminal(_) => Ok(()),
+            Output::Recording(recorder) => recorder.resize(width, height),
+        }
+    }
+
+    /// The terminal itself, for output that mustn't end up in a recording.
+    fn terminal(&mut self) -> &mut Stdout {
+        match self {
+            Output::Terminal(stdout) => stdout,
+            Output::Recording(recorder) => recorder.terminal(),
+        }
+    }
+
+    /// Writes a whole frame and flushes it, so it is recorded as one.
+    fn frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.write_all(frame)?;
+        self.flush()
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Terminal(stdout) => stdout.write(buf),
+            Output::Recording(recorder) => recorder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Terminal(stdout) => stdout.flush(),
+            Output::Recording(recorder) => recorder.flush(),
+        }
+    }
+}
+
+/// Changes to the viewport the writer hasn't applied yet, and a way for it
+/// to sleep until there are some.
+#[derive(Default)]
+struct ViewChanges {
+    queue: Mutex<Vec<ViewChange>>,
+    ready: Condvar,
+}
+
+/// A change to the viewport asked for by the input loop.
+#[derive(Debug, Clone)]
+enum ViewChange {
+    /// The terminal is now this many columns by rows.
+    Resize(u16, u16),
+    /// Scroll by this many terminal cells, which stops following the search.
+    Pan(isize, isize),
+    Zoom {
+        out: bool,
+    },
+    ToggleFollow,
+    ToggleMinimap,
+    /// Replay this many steps forwards, or backwards if negative.
+    Step(isize),
+    /// Replay from before the maze was generated.
+    Rewind,
+    /// Go back to showing the search as it happens.
+    Live,
+    /// Show or hide each space's predecessor, which only works while paused,
+    /// replaying or once the search is done.
+    ToggleArrows,
+    /// Show this message in the HUD.
+    Notice(String),
+}
+
+impl View {
+    /// Terminal rows under the grid: the HUD, then the frame stats if shown.
+    fn hud_rows(&self) -> usize {
+        Hud::ROWS + self.frame_stats as usize
+    }
+
+    fn push(&self, change: ViewChange) {
+        self.changes.queue.lock().unwrap().push(change);
+        self.changes.ready.notify_all();
+    }
+
+    fn drain(&self) -> Vec<ViewChange> {
+        std::mem::take(&mut *self.changes.queue.lock().unwrap())
+    }
+
+    /// Wakes the writer from [`View::wait`] to look at something shared
+    /// besides the queue, such as the theme or the solvers' progress.
+    fn wake(&self) {
+        let _queue = self.changes.queue.lock().unwrap();
+        self.changes.ready.notify_all();
+    }
+
+    /// Sleeps until a change is pushed, `woken` turns true after
+    /// [`View::wake`], or `deadline` passes.
+    fn wait(&self, deadline: Option<Instant>, woken: impl Fn() -> bool) {
+        let mut queue = self.changes.queue.lock().unwrap();
+        while queue.is_empty() && !woken() {
+            queue = match deadline {
+                Some(deadline) => {
+                    let Some(timeout) = deadline.checked_duration_since(Instant::now()) else {
+                        return;
+                    };
+                    self.changes.ready.wait_timeout(queue, timeout).unwrap().0
+                }
+                None => self.changes.ready.wait(queue).unwrap(),
+            };
+        }
+    }
+}
+
+/// Steps `{` and `}` move through a replay, for getting about a long one.
+const REPLAY_JUMP: isize = 50;
+
+/// Handles input until the writer thread is done, which is when it is
+/// cancelled through `progress` or, with [`View::hold`], that long after the
+/// solvers finish.
+fn wait_for_writer(
+    writer_thread: JoinHandle<()>,
+    view: &View,
+    progress: &[Arc<Progress>],
+    seed: u64,
+) -> io::Result<Next> {
+    let cancel = || {
+        progress.iter().for_each(|progress| progress.cancel());
+        view.wake();
+    };
+    let mut next = Next::NewMaze;
+    let mut drag = None;
+    while !writer_thread.is_finished() {
+        let event = poll(Duration::from_millis(15)).and_then(|ready| ready.then(read).transpose());
+        let event = match event {
+            Ok(Some(event)) => event,
+            Ok(None) => continue,
+            // The solver and writer are stopped before giving up, so neither
+            // is left drawing while the terminal is restored.
+            Err(err) => {
+                cancel();
+                let _ = writer_thread.join();
+                return Err(err);
+            }
+        };
+        match event {
+            Event::Key(event) => match event.code {
+                KeyCode::Char('q') => {
+                    cancel();
+                    next = Next::Quit;
+                }
+                KeyCode::Char('r') => {
+                    cancel();
+                    next = Next::Regenerate;
+                }
+                KeyCode::Char('n') => {
+                    cancel();
+                    next = Next::NewMaze;
+                }
+                KeyCode::Char('c') => {
+                    // Written between frames, but never recorded: replaying a
+                    // recording mustn't overwrite the viewer's clipboard.
+                    let mut output = view.output.lock().unwrap();
+                    let terminal = output.terminal();
+                    let copied = screen_state::copy_to_clipboard(terminal, &seed.to_string());
+                    if let Err(err) = copied {
+                        view.push(ViewChange::Notice(format!("can't copy the seed: {err}")));
+                    }
+                }
+                KeyCode::Char('t') => {
+                    let next = (view.theme.load(Ordering::Relaxed) + 1) % view.themes.len();
+                    view.theme.store(next, Ordering::Relaxed);
+                    view.wake();
+                }
+                KeyCode::Left => view.push(ViewChange::Pan(-4, 0)),
+                KeyCode::Right => view.push(ViewChange::Pan(4, 0)),
+                KeyCode::Up => view.push(ViewChange::Pan(0, -2)),
+                KeyCode::Down => view.push(ViewChange::Pan(0, 2)),
+                KeyCode::Char('+' | '=') => view.push(ViewChange::Zoom { out: false }),
+                KeyCode::Char('-') => view.push(ViewChange::Zoom { out: true }),
+                KeyCode::Char('f') => view.push(ViewChange::ToggleFollow),
+                KeyCode::Char('m') => view.push(ViewChange::ToggleMinimap),
+                KeyCode::Char('[') => view.push(ViewChange::Step(-1)),
+                KeyCode::Char(']') => view.push(ViewChange::Step(1)),
+                KeyCode::Char('{') => view.push(ViewChange::Step(-REPLAY_JUMP)),
+                KeyCode::Char('}') => view.push(ViewChange::Step(REPLAY_JUMP)),
+                KeyCode::Home => view.push(ViewChange::Rewind),
+                KeyCode::End => view.push(ViewChange::Live),
+                KeyCode::Char(' ') => {
+                    let paused = !progress.iter().any(|progress| progress.is_paused());
+                    progress
+                        .iter()
+                        .for_each(|progress| progress.set_paused(paused));
+                    view.wake();
+                }
+                KeyCode::Char('a') => view.push(ViewChange::ToggleArrows),
+                _ => {}
+            },
+            // Dragging moves the grid along with the mouse.
+            Event::Mouse(event) => match event.kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    drag = Some((event.column, event.row));
+                }
+                MouseEventKind::Drag(MouseButton::Left) => {
+                    if let Some((column, row)) = drag {
+                        let dx = column as isize - event.column as isize;
+                        let dy = row as isize - event.row as isize;
+                        view.push(ViewChange::Pan(dx, dy));
+                    }
+                    drag = Some((event.column, event.row));
+                }
+                MouseEventKind::Up(_) => drag = None,
+                _ => {}
+            },
+            Event::Resize(width, height) => view.push(ViewChange::Resize(width, height)),
+            _ => {}
+        }
+    }
+    let _ = writer_thread.join();
+    Ok(next)
+}
+
+/// What the writer thread draws besides the grid.
+struct Screen {
+    /// Terminal size when the maze was built.
+    size: (usize, usize),
+    hud: Hud,
+    progress: Arc<Progress>,
+}
+
+/// Grid cells the minimap reads per frame. Bigger grids take more frames to
+/// bring the whole minimap up to date.
+const MINIMAP_BUDGET: usize = 1 << 16;
+
+fn writer_thread(
+    maze_thread: JoinHandle<()>,
+    mut timeline: Timeline,
+    events: Receiver<Change>,
+    view: View,
+    mut screen: Screen,
+) -> io::Result<()> {
+    let grid = timeline.grid();
+    let heatmap = view
+        .heatmap
+        .then(|| Heatmap::for_grid(grid.width, grid.height));
+    // A fresh buffer knows nothing of what is on screen, so building one for
+    // a new terminal size repaints everything. The grid starts out centred in
+    // the space above the HUD and the frame stats, if shown.
+    let layout = |(width, height): (usize, usize), theme: usize, zoom: usize, grid: &Grid| {
+        let mut buf = Buffer::with_mode(width, height, view.render);
+        buf.set_viewport(Viewport {
+            height: height.saturating_sub(view.hud_rows()),
+            ..buf.viewport()
+        });
+        buf.set_zoom(zoom, grid);
+        buf.centre_on(Point::new(grid.width / 2, grid.height / 2), grid);
+        buf.set_heatmap(heatmap);
+        buf.set_color_depth(view.depth);
+        buf.set_theme(view.themes[theme].clone());
+        buf
+    };
+    let mut theme = view.theme.load(Ordering::Relaxed);
+    let mut buf = layout(screen.size, theme, 1, grid);
+    let mut minimap = Minimap::new(grid.width, grid.height);
+    minimap.refresh_all(grid);
+    let mut follow = true;
+    // The minimap only starts out shown when the grid doesn't fit.
+    let mut show_minimap = {
+        let (width, height) = buf.content_size(grid);
+        width > buf.viewport().width || height > buf.viewport().height
+    };
+    let mut max_distance = grid.spaces.iter().filter_map(Space::distance).max();
+    // Set when the whole viewport has to be drawn again rather than just the
+    // spaces that changed.
+    let mut redraw = true;
+    let mut changed = vec![];
+    let mut arrows = false;
+    let mut frame_stats = FrameStats::default();
+    let mut frame = Vec::new();
+
+    let started = Instant::now();
+    // The clock stops once the solver is done.
+    let mut solve_time = None;
+    let mut next_frame = started;
+    // Set once the last frame shows everything there is to show, so nothing
+    // changes on screen until the input loop asks for something.
+    let mut settled = false;
+    let mut paused = false;
+    loop {
+        if settled {
+            let deadline = solve_time
+                .zip(view.hold)
+                .map(|(solved, hold)| started + solved + hold);
+            view.wait(deadline, || {
+                screen.progress.is_cancelled()
+                    || screen.progress.is_paused() != paused
+                    || view.theme.load(Ordering::Relaxed) != theme
+            });
+            // Drawn straight away, there being no changes to wait for.
+            next_frame = Instant::now();
+        } else {
+            // However many steps the solver takes before the next frame is
+            // due, they are drawn together in that frame.
+            next_frame += view.frame_time;
+            let now = Instant::now();
+            if next_frame < now {
+                // Running behind, so start counting again rather than rush.
+                next_frame = now;
+            }
+        }
+        // Checked before taking changes so the last of them are still drawn.
+        if maze_thread.is_finished() {
+            solve_time.get_or_insert_with(|| started.elapsed());
+        }
+        // Whether every change the solver has sent is in, which is only
+        // known when waiting for the next one times out.
+        let mut drained = settled;
+        while let Some(wait) = next_frame.checked_duration_since(Instant::now()) {
+            let Ok(change) = events.recv_timeout(wait) else {
+                drained = true;
+                break;
+            };
+            max_distance = max_distance.max(change.space.distance());
+            if timeline.record(change) {
+                changed.push(change.point);
+            }
+        }
+        if screen.progress.is_cancelled() {
+            break;
+        }
+        let composing = Instant::now();
+        if view.theme.load(Ordering::Relaxed) != theme {
+            theme = view.theme.load(Ordering::Relaxed);
+            buf.set_theme(view.themes[theme].clone());
+            redraw = true;
+        }
+        let stats = screen.progress.stats();
+        paused = stats.paused;
+        for change in view.drain() {
+            let grid = timeline.grid();
+            match change {
+                ViewChange::Resize(width, height) => {
+                    let size = (width as usize, height as usize);
+                    buf = layout(size, theme, buf.viewport().zoom, grid);
+                    let _ = view.output.lock().unwrap().resize(width, height);
+                }
+                ViewChange::Pan(dx, dy) => {
+                    follow = false;
+                    buf.pan(dx, dy, grid);
+                }
+                ViewChange::Zoom { out } => {
+                    if let Some(zoom) = buf.viewport().step_zoom(out) {
+                        buf.set_zoom(zoom, grid);
+                    }
+                }
+                ViewChange::ToggleFollow => follow = !follow,
+                ViewChange::ToggleMinimap => show_minimap = !show_minimap,
+                ViewChange::Step(by) => timeline.step(by, &mut changed),
+                ViewChange::Rewind => timeline.seek(0, &mut changed),
+                ViewChange::Live => timeline.seek(timeline.len(), &mut changed),
+                ViewChange::ToggleArrows => arrows = !arrows,
+                ViewChange::Notice(notice) => screen.hud.notice = Some(notice),
+            }
+            redraw = true;
+        }
+        let grid = timeline.grid();
+        // The search tree only holds still to be looked at while paused,
+        // replaying or done, so the arrows go once it moves on.
+        let moving = !stats.paused && solve_time.is_none();
+        if arrows && moving && timeline.is_live() {
+            arrows = false;
+            redraw = true;
+        }
+        // A replay follows the step it shows rather than the live search.
+        let current = match timeline.is_live() {
+            true => stats.current,
+            false => timeline.current(),
+        };
+        if let (true, Some(current)) = (follow, current) {
+            let before = buf.viewport();
+            buf.follow(current, grid);
+            redraw |= buf.viewport() != before;
+        }
+        for point in changed.drain(..) {
+            minimap.mark_changed(point);
+            buf.draw_point(grid, point);
+        }
+        if redraw {
+            buf.draw_grid(grid);
+            redraw = false;
+        }
+        if arrows {
+            buf.draw_arrows(grid, timeline.preds());
+        }
+
+        let legend = heatmap.map(|heatmap| (heatmap, max_distance.unwrap_or(0)));
+        let hud_row = buf.viewport().height;
+        let replay = (!timeline.is_live()).then(|| (timeline.position(), timeline.len()));
+        let elapsed = solve_time.unwrap_or_else(|| started.elapsed());
+        screen
+            .hud
+            .draw(&mut buf, hud_row, stats, elapsed, replay, legend);
+        let viewport = buf.viewport();
+        let (minimap_width, minimap_height) = minimap.size();
+        let minimap_shown =
+            show_minimap && viewport.width > minimap_width && viewport.height > minimap_height;
+        if minimap_shown {
+            minimap.refresh(grid, MINIMAP_BUDGET);
+            let x = viewport.width - minimap_width;
+            let visible = buf.visible();
+            minimap.draw(&mut buf, x, 0, visible);
+        }
+        if view.frame_stats {
+            frame_stats.draw(&mut buf, hud_row + Hud::ROWS);
+        }
+        frame.clear();
+        let _ = buf.flush_changes(&mut frame);
+        let _ = view.output.lock().unwrap().frame(&frame);
+        frame_stats.record(composing, composing.elapsed(), frame.len());
+
+        if let (Some(solved), Some(hold)) = (solve_time, view.hold) {
+            if started.elapsed() >= solved + hold {
+                break;
+            }
+        }
+        // The solver was done before its last changes were taken, so there
+        // are no more to come.
+        settled = solve_time.is_some() && drained && !(minimap_shown && minimap.is_stale());
+    }
+    let _ = maze_thread.join();
+    Ok(())
+}
+
+/// Rows under the panes in race mode: each lane's status, then the result.
+const RACE_ROWS: usize = Hud::ROWS + 1;
+
+/// How long a solved maze, or the result of a race, stays up before the next
+/// maze with [`App::loop_mazes`].
+pub const RESULT_TIME: Duration = Duration::from_secs(3);
+
+/// Draws a race like [`writer_thread`] draws one solver, with `grids` and
+/// `events` holding each lane's copy of the maze and its changes by lane.
+fn race_writer(
+    race: Race,
+    solvers: Vec<JoinHandle<Solution>>,
+    mut grids: Vec<Grid>,
+    events: Receiver<(usize, Change)>,
+    view: View,
+    size: (usize, usize),
+    mut hud: Hud,
+) -> io::Result<()> {
+    let lanes = race.lanes.len();
+    let heatmap = view
+        .heatmap
+        .then(|| Heatmap::for_grid(grids[0].width, grids[0].height));
+    // One buffer per pane with a column between them, and one for the result
+    // line along the bottom.
+    let layout = |(width, height): (usize, usize), theme: usize, grids: &[Grid]| {
+        let pane_width = ((width + 1) / lanes).saturating_sub(1);
+        let panes: Vec<Buffer> = grids
+            .iter()
+            .enumerate()
+            .map(|(i, grid)| {
+                let mut buf = Buffer::with_mode(pane_width, height.saturating_sub(1), view.render);
+                buf.set_origin((i * (pane_width + 1)) as u16, 0);
+                buf.set_viewport(Viewport {
+                    height: height.saturating_sub(RACE_ROWS),
+                    ..buf.viewport()
+                });
+                buf.centre_on(Point::new(grid.width / 2, grid.height / 2), grid);
+                buf.set_heatmap(heatmap);
+                buf.set_color_depth(view.depth);
+                buf.set_theme(view.themes[theme].clone());
+                buf.draw_grid(grid);
+                buf
+            })
+            .collect();
+        let mut result = Buffer::new(width, 1);
+        result.set_origin(0, height.saturating_sub(1) as u16);
+        result.set_color_depth(view.depth);
+        (panes, result)
+    };
+    let mut theme = view.theme.load(Ordering::Relaxed);
+    let (mut panes, mut result) = layout(size, theme, &grids);
+
+    let started = Instant::now();
+    let mut times = vec![None; lanes];
+    let mut frame = Vec::new();
+    let mut done_at: Option<Instant> = None;
+    let mut next_frame = started;
+    // As in `writer_thread`, set once the result is up and nothing more is
+    // coming.
+    let mut settled = false;
+    loop {
+        if settled {
+            let deadline = done_at.zip(view.hold).map(|(done_at, hold)| done_at + hold);
+            view.wait(deadline, || {
+                race.lanes[0].progress.is_cancelled() || view.theme.load(Ordering::Relaxed) != theme
+            });
+            next_frame = Instant::now();
+        } else {
+            next_frame += view.frame_time;
+            let now = Instant::now();
+            if next_frame < now {
+                next_frame = now;
+            }
+        }
+        let finished: Vec<bool> = solvers.iter().map(JoinHandle::is_finished).collect();
+        let mut drained = settled;
+        while let Some(wait) = next_frame.checked_duration_since(Instant::now()) {
+            let Ok((lane, Change { point, space, .. })) = events.recv_timeout(wait) else {
+                drained = true;
+                break;
+            };
+            if let Some(slot) = grids[lane].get_mut(point) {
+                *slot = space;
+            }
+            panes[lane].draw_point(&grids[lane], point);
+        }
+        if race.lanes[0].progress.is_cancelled() {
+            break;
+        }
+        if view.theme.load(Ordering::Relaxed) != theme {
+            theme = view.theme.load(Ordering::Relaxed);
+            for (buf, grid) in panes.iter_mut().zip(&grids) {
+                buf.set_theme(view.themes[theme].clone());
+                buf.draw_grid(grid);
+            }
+        }
+        // Panning and zooming don't apply to the panes, which fit the screen.
+        for change in view.drain() {
+            match change {
+                ViewChange::Resize(width, height) => {
+                    (panes, result) = layout((width as usize, height as usize), theme, &grids);
+                    let _ = view.output.lock().unwrap().resize(width, height);
+                }
+                ViewChange::Notice(notice) => hud.notice = Some(notice),
+                _ => {}
+            }
+        }
+
+        let stats = race.stats();
+        frame.clear();
+        let all_done = finished.iter().all(|&done| done);
+        let standings = all_done.then(|| Standings::from_stats(&stats));
+        for (i, (lane, buf)) in race.lanes.iter().zip(&mut panes).enumerate() {
+            if finished[i] && times[i].is_none() {
+                times[i] = Some(started.elapsed());
+            }
+            let mut won = vec![];
+            if let Some(standings) = &standings {
+                if standings.fewest_expanded.contains(&i) {
+                    won.push("fewest expanded");
+                }
+                if standings.shortest_path.contains(&i) {
+                    won.push("shortest path");
+                }
+            }
+            let elapsed = times[i].unwrap_or_else(|| started.elapsed());
+            let row = buf.viewport().height;
+            hud::draw_lane(buf, row, lane.solver.name(), stats[i], elapsed, &won);
+            let _ = buf.flush_changes(&mut frame);
+        }
+        let text = match &standings {
+            Some(standings) => race.describe(standings),
+            None => "racing".to_string(),
+        };
+        hud.draw_race(&mut result, 0, &text);
+        let _ = result.flush_changes(&mut frame);
+        let _ = view.output.lock().unwrap().frame(&frame);
+
+        if all_done {
+            let done_at = done_at.get_or_insert_with(Instant::now);
+            if view.hold.is_some_and(|hold| done_at.elapsed() >= hold) {
+                break;
+            }
+        }
+        settled = all_done && drained;
+    }
+    for solver in solvers {
+        let _ = solver.join();
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::io;
+#[cfg(feature = "tui")]
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use crate::grid::Grid;
+#[cfg(feature = "tui")]
+use crate::maze::Change;
+use crate::maze::{GeneratorKind, Progress, SolverKind};
+
+/// Builds and solves many mazes in a row to see how a solver does on average.
+pub struct Bench {
+    pub generator: GeneratorKind,
+    pub solver: SolverKind,
+    pub width: usize,
+    pub height: usize,
+    /// Also replay each solve into an off-screen buffer and count the bytes
+    /// drawing it takes, which needs the `tui` feature.
+    pub frames: bool,
+}
+
+/// What [`Bench::run`] found, written out a line per figure by its
+/// [`fmt::Display`].
+#[derive(Debug)]
+pub struct Report {
+    pub generator: &'static str,
+    pub solver: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub first_seed: u64,
+    pub count: usize,
+    /// Mazes built, which leaves out grids too small for one.
+    pub built: usize,
+    /// Mazes with a path from start to end.
+    pub solved: usize,
+    pub visited: usize,
+    pub path_len: usize,
+    pub elapsed: Duration,
+    pub frame_bytes: Option<FrameBytes>,
+}
+
+impl Bench {
+    /// Builds and solves `count` mazes from seeds counting up from
+    /// `first_seed`.
+    pub fn run(&self, count: usize, first_seed: u64) -> io::Result<Report> {
+        if self.frames && !cfg!(feature = "tui") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "frame sizes need the `tui` feature",
+            ));
+        }
+        let mut report = Report {
+            generator: self.generator.name(),
+            solver: self.solver.name(),
+            width: self.width,
+            height: self.height,
+            first_seed,
+            count,
+            built: 0,
+            solved: 0,
+            visited: 0,
+            path_len: 0,
+            elapsed: Duration::ZERO,
+            frame_bytes: self.frames.then(FrameBytes::default),
+        };
+        for i in 0..count {
+            let seed = first_seed.wrapping_add(i as u64);
+            let Some(generated) = self
+                .generator
+                .build(Grid::new(self.width, self.height), seed)
+            else {
+                continue;
+            };
+            report.built += 1;
+            #[cfg(feature = "tui")]
+            let grid = generated.grid.lock().unwrap().clone();
+            let progress = Progress::default();
+            #[cfg(feature = "tui")]
+            let events = progress.subscribe();
+            let now = Instant::now();
+            let solution =
+                self.solver
+                    .solve(generated.start, generated.grid, Duration::ZERO, &progress);
+            report.elapsed += now.elapsed();
+            #[cfg(feature = "tui")]
+            if let Some(frame_bytes) = &mut report.frame_bytes {
+                frame_bytes.replay(grid, &events)?;
+            }
+            report.visited += solution.visited.len();
+            if !solution.path.is_empty() {
+                report.solved += 1;
+                report.path_len += solution.path.len();
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let built = self.built.max(1) as f64;
+        writeln!(f, "generator: {}", self.generator)?;
+        writeln!(f, "solver: {}", self.solver)?;
+        writeln!(f, "size: {}x{}", self.width, self.height)?;
+        writeln!(f, "first seed: {}", self.first_seed)?;
+        writeln!(f, "built: {}/{}", self.built, self.count)?;
+        writeln!(f, "solved: {}/{}", self.solved, self.built)?;
+        writeln!(f, "mean visited: {:.1}", self.visited as f64 / built)?;
+        writeln!(
+            f,
+            "mean path length: {:.1}",
+            self.path_len as f64 / self.solved.max(1) as f64
+        )?;
+        writeln!(f, "mean solve time: {:?}", self.elapsed.div_f64(built))?;
+        if let Some(frame_bytes) = &self.frame_bytes {
+            writeln!(
+                f,
+                "mean first frame: {:.0} bytes",
+                frame_bytes.first as f64 / built
+            )?;
+            writeln!(
+                f,
+                "mean frame: {:.1} bytes over {} frames",
+                frame_bytes.rest as f64 / frame_bytes.frames.max(1) as f64,
+                frame_bytes.frames
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Bytes written to the terminal while replaying solves with
+/// [`FrameBytes::replay`].
+#[derive(Debug, Default)]
+pub struct FrameBytes {
+    /// Drawing each whole grid the first time.
+    pub first: usize,
+    /// Every frame after that, and how many there were.
+    pub rest: usize,
+    pub frames: usize,
+}
+
+#[cfg(feature = "tui")]
+impl FrameBytes {
+    /// Changes drawn in each frame of a replay, about what the writer thread
+    /// sees in one tick with a short solver delay.
+    const CHANGES_PER_FRAME: usize = 16;
+
+    /// Draws `grid` into a buffer the size of it, then applies the solver's
+    /// `events` a frame at a time the way the writer thread does, counting
+    /// what each flush writes.
+    pub fn replay(&mut self, mut grid: Grid, events: &Receiver<Change>) -> io::Result<()> {
+        use crate::buffer::Buffer;
+
+        let mut buf = Buffer::new(grid.width, grid.height);
+        let mut out = Vec::new();
+        buf.flush_diff(&mut out, &grid)?;
+        self.first += out.len();
+
+        let changes: Vec<Change> = events.try_iter().collect();
+        for frame in changes.chunks(Self::CHANGES_PER_FRAME) {
+            for &Change { point, space, .. } in frame {
+                if let Some(slot) = grid.get_mut(point) {
+                    *slot = space;
+                }
+                buf.draw_point(&grid, point);
+            }
+            out.clear();
+            buf.flush_changes(&mut out)?;
+            self.rest += out.len();
+            self.frames += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod bench_tests {
+    use super::Bench;
+    use crate::maze::{GeneratorKind, SolverKind};
+
+    #[test]
+    fn manual_test_bench_report() {
+        let bench = Bench {
+            generator: GeneratorKind::Random,
+            solver: SolverKind::Bfs,
+            width: 20,
+            height: 10,
+            frames: false,
+        };
+
+        let report = bench.run(5, 11).unwrap();
+        assert_eq!((5, 5), (report.count, report.built));
+        assert!(report.solved <= report.built);
+        assert!(report.frame_bytes.is_none());
+        let text = report.to_string();
+        assert!(text.starts_with("generator: random\nsolver: bfs\nsize: 20x10\nfirst seed: 11\n"));
+        assert!(text.contains("built: 5/5\n"));
+
+        // Grids too small for a maze are left out of the means.
+        let bench = Bench {
+            width: 1,
+            height: 1,
+            ..bench
+        };
+        let report = bench.run(3, 11).unwrap();
+        assert_eq!(0, report.built);
+        assert!(report.to_string().contains("mean visited: 0.0\n"));
+    }
+}
@@ -1,14 +1,17 @@
-use std::io;
+use std::{cmp::Ordering, io};
 
 use crossterm::{
     cursor::MoveTo,
-    style::{style, Color, Colors, PrintStyledContent, ResetColor, SetColors},
-    terminal::{Clear, ClearType},
+    style::{Color, Colors, Print, ResetColor, SetColors},
     QueueableCommand,
 };
 
+use crate::color_depth::ColorDepth;
 use crate::grid::Grid;
+use crate::point::Point;
 use crate::space::Space;
+use crate::theme::{Theme, DEFAULT_THEME};
+use crate::viewport::{self, Viewport};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Cell {
@@ -28,53 +31,166 @@ impl Default for Cell {
     }
 }
 
-impl From<Space> for Cell {
-    fn from(value: Space) -> Self {
-        match value {
-            Space::Obstacle => Cell {
-                ch: ' ',
-                colors: Colors {
-                    foreground: None,
-                    background: Some(Color::DarkRed),
-                },
-            },
-            Space::Empty => Cell {
+impl Cell {
+    /// The single colour that stands for this cell when it is squeezed into half
+    /// of a terminal character: its background, or the glyph colour for cells
+    /// drawn with a visible glyph.
+    pub fn block_color(&self) -> Option<Color> {
+        match self.colors.background {
+            Some(color) => Some(color),
+            None if self.ch != ' ' => self.colors.foreground,
+            None => None,
+        }
+    }
+
+    /// Packs two vertically stacked cells into one terminal character using the
+    /// upper and lower half block glyphs.
+    pub fn half_block(top: Cell, bottom: Cell) -> Self {
+        match (top.block_color(), bottom.block_color()) {
+            (None, None) => Cell {
                 ch: ' ',
                 colors: Colors {
                     foreground: None,
                     background: None,
                 },
             },
-            Space::Visited => Cell {
-                ch: 'O',
-                colors: Colors {
-                    foreground: Some(Color::White),
-                    background: None,
-                },
-            },
-            Space::Path => Cell {
-                ch: ' ',
+            (Some(top), bottom) => Cell {
+                ch: '▀',
                 colors: Colors {
-                    foreground: None,
-                    background: Some(Color::AnsiValue(7)),
+                    foreground: Some(top),
+                    background: bottom,
                 },
             },
-            Space::Start(_) => Cell {
-                ch: ' ',
+            (None, Some(bottom)) => Cell {
+                ch: '▄',
                 colors: Colors {
-                    foreground: None,
-                    background: Some(Color::DarkBlue),
+                    foreground: Some(bottom),
+                    background: None,
                 },
             },
-            Space::End(_) => Cell {
-                ch: ' ',
-                colors: Colors {
-                    foreground: None,
-                    background: Some(Color::DarkGreen),
-                },
+        }
+    }
+}
+
+/// Colours visited spaces by their distance from the start, so the search
+/// wavefront shows up as bands of colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heatmap {
+    /// Distance mapped to the far end of the gradient. Further cells are
+    /// clamped, so a cell keeps its colour however far the search goes.
+    pub span: u32,
+}
+
+impl Heatmap {
+    /// Blue through green and yellow to red.
+    const STOPS: [(u8, u8, u8); 5] = [
+        (48, 64, 200),
+        (0, 190, 220),
+        (70, 200, 70),
+        (240, 215, 40),
+        (220, 40, 30),
+    ];
+
+    /// A span as long as a walk along two edges of a `width` by `height` grid,
+    /// which few shortest paths exceed.
+    pub fn for_grid(width: usize, height: usize) -> Self {
+        Self {
+            span: (width + height).max(1) as u32,
+        }
+    }
+
+    pub fn color(&self, distance: u32) -> Color {
+        let t = distance.min(self.span) as f32 / self.span.max(1) as f32;
+        let scaled = t * (Self::STOPS.len() - 1) as f32;
+        let i = (scaled as usize).min(Self::STOPS.len() - 2);
+        let frac = scaled - i as f32;
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * frac).round() as u8;
+        let (from, to) = (Self::STOPS[i], Self::STOPS[i + 1]);
+        Color::Rgb {
+            r: lerp(from.0, to.0),
+            g: lerp(from.1, to.1),
+            b: lerp(from.2, to.2),
+        }
+    }
+
+    pub fn cell(&self, distance: u32) -> Cell {
+        Cell {
+            ch: ' ',
+            colors: Colors {
+                foreground: None,
+                background: Some(self.color(distance)),
             },
         }
     }
+
+    /// Draws a `0 ▇▇▇▇ max` legend into `buf` at `x`, `y`, `width` swatches
+    /// wide, covering the distances seen so far. Returns the column after it.
+    pub fn draw_legend(
+        &self,
+        buf: &mut Buffer,
+        x: usize,
+        y: usize,
+        max: u32,
+        width: usize,
+    ) -> usize {
+        let mut x = buf.put_str(x, y, "0 ", Cell::default().colors);
+        for i in 0..width {
+            let distance = (max as usize * i / width.saturating_sub(1).max(1)) as u32;
+            if buf.put(x, y, ' ', self.cell(distance).colors).is_some() {
+                x += 1;
+            }
+        }
+        buf.put_str(x, y, &format!(" {max}"), Cell::default().colors)
+    }
+}
+
+/// How grid spaces are laid out on terminal characters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum RenderMode {
+    /// One space per character.
+    #[default]
+    Full,
+    /// Two spaces stacked in each character with `▀`/`▄`, so cells come out
+    /// roughly square.
+    HalfBlock,
+}
+
+impl RenderMode {
+    /// Grid rows drawn in each terminal row.
+    pub fn rows_per_cell(&self) -> usize {
+        match self {
+            RenderMode::Full => 1,
+            RenderMode::HalfBlock => 2,
+        }
+    }
+
+    /// The cell shown at terminal position `x`, `y`, or `None` if it lies
+    /// outside the grid. `space` looks up the space at a grid position and
+    /// `paint` turns a single space into a cell.
+    pub fn cell(
+        &self,
+        x: usize,
+        y: usize,
+        space: impl Fn(usize, usize) -> Option<Space>,
+        paint: impl Fn(Space) -> Cell,
+    ) -> Option<Cell> {
+        match self {
+            RenderMode::Full => space(x, y).map(paint),
+            RenderMode::HalfBlock => {
+                let top = space(x, y * 2)?;
+                let bottom = space(x, y * 2 + 1).unwrap_or(Space::Empty);
+                Some(Cell::half_block(paint(top), paint(bottom)))
+            }
+        }
+    }
+}
+
+/// The look of `value` in the default theme.
+impl From<Space> for Cell {
+    fn from(value: Space) -> Self {
+        DEFAULT_THEME.cell(value)
+    }
 }
 
 impl From<&Space> for Cell {
@@ -83,25 +199,201 @@ impl From<&Space> for Cell {
     }
 }
 
+/// A grid of cells to draw, plus a copy of what was last written to the
+/// terminal so `flush_diff` only sends the cells that changed. The grid is
+/// drawn inside the viewport and text such as the HUD can be put into the rows
+/// around it.
 pub struct Buffer {
+    /// Write these through the drawing methods, which note the cells that
+    /// changed so a flush only has to look at those.
     pub cells: Vec<Cell>,
+    screen: Vec<Cell>,
+    /// Cells that may differ from `screen`, and which of them are listed.
+    pending: Vec<usize>,
+    dirty: Vec<bool>,
     width: usize,
     height: usize,
+    /// Screen position of the buffer's top-left corner.
+    origin: (u16, u16),
+    mode: RenderMode,
+    viewport: Viewport,
+    heatmap: Option<Heatmap>,
+    theme: Theme,
+    depth: ColorDepth,
 }
 
 impl Buffer {
+    /// Stands for a screen cell whose contents aren't known, so it never
+    /// matches a real cell.
+    const UNKNOWN: Cell = Cell {
+        ch: '\0',
+        colors: Colors {
+            foreground: None,
+            background: None,
+        },
+    };
+
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_mode(width, height, RenderMode::Full)
+    }
+
+    pub fn with_mode(width: usize, height: usize, mode: RenderMode) -> Self {
         Self {
             cells: vec![Cell::default(); width * height],
+            screen: vec![Buffer::UNKNOWN; width * height],
+            pending: (0..width * height).collect(),
+            dirty: vec![true; width * height],
             width,
             height,
+            origin: (0, 0),
+            mode,
+            viewport: Viewport {
+                width,
+                height,
+                x: 0,
+                y: 0,
+                zoom: 1,
+            },
+            heatmap: None,
+            theme: Theme::default(),
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    /// Moves the buffer on screen, so several can share it side by side.
+    /// Everything is written again at the new spot on the next flush.
+    pub fn set_origin(&mut self, x: u16, y: u16) {
+        self.origin = (x, y);
+        self.invalidate();
+    }
+
+    /// The viewport is clipped to the buffer.
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = Viewport {
+            width: viewport.width.min(self.width),
+            height: viewport.height.min(self.height),
+            ..viewport
+        };
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    /// The size of `grid` in terminal cells at the viewport's zoom.
+    pub fn content_size(&self, grid: &Grid) -> (usize, usize) {
+        let zoom = self.viewport.zoom.max(1);
+        (
+            grid.width.div_ceil(zoom),
+            grid.height
+                .div_ceil(zoom)
+                .div_ceil(self.mode.rows_per_cell()),
+        )
+    }
+
+    /// The terminal cell of the grid `point` falls in, relative to the grid's
+    /// top-left corner.
+    fn content_point(&self, point: Point) -> (usize, usize) {
+        let zoom = self.viewport.zoom.max(1);
+        (point.x / zoom, point.y / zoom / self.mode.rows_per_cell())
+    }
+
+    /// The part of the grid the viewport shows: the grid position of its
+    /// top-left corner, which is negative when there is a margin, and its
+    /// width and height in grid cells.
+    pub fn visible(&self) -> (isize, isize, usize, usize) {
+        let zoom = self.viewport.zoom.max(1);
+        let rows = zoom * self.mode.rows_per_cell();
+        (
+            self.viewport.x * zoom as isize,
+            self.viewport.y * rows as isize,
+            self.viewport.width * zoom,
+            self.viewport.height * rows,
+        )
+    }
+
+    /// Scrolls the viewport by `dx`, `dy` terminal cells.
+    pub fn pan(&mut self, dx: isize, dy: isize, grid: &Grid) {
+        let (width, height) = self.content_size(grid);
+        self.viewport.pan(dx, dy, width, height);
+    }
+
+    /// Changes the zoom, keeping the grid position in the middle of the
+    /// viewport where it was.
+    pub fn set_zoom(&mut self, zoom: usize, grid: &Grid) {
+        let old = self.viewport.zoom.max(1);
+        let rows = self.mode.rows_per_cell();
+        let centre = Point::new(
+            (self.viewport.x + self.viewport.width as isize / 2).max(0) as usize * old,
+            (self.viewport.y + self.viewport.height as isize / 2).max(0) as usize * old * rows,
+        );
+        self.viewport.zoom = zoom.max(1);
+        self.centre_on(centre, grid);
+    }
+
+    /// Scrolls so grid `point` is in the middle of the viewport.
+    pub fn centre_on(&mut self, point: Point, grid: &Grid) {
+        let (x, y) = self.content_point(point);
+        let (width, height) = self.content_size(grid);
+        self.viewport.centre_on(x, y, width, height);
+    }
+
+    /// Scrolls to keep grid `point` in view, recentring only once it strays
+    /// from the middle of the viewport so the picture doesn't jitter.
+    pub fn follow(&mut self, point: Point, grid: &Grid) {
+        let (x, y) = self.content_point(point);
+        if !self.viewport.is_near_centre(x, y) {
+            self.centre_on(point, grid);
+        }
+    }
+
+    /// Colours are downsampled to what `depth` can show as they are written.
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.depth = depth;
+        self.invalidate();
+    }
+
+    /// Switches theme and forces the next `flush_diff` to repaint every cell.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.invalidate();
+    }
+
+    /// Forgets what is on screen so the next `flush_diff` redraws everything.
+    pub fn invalidate(&mut self) {
+        self.screen.fill(Buffer::UNKNOWN);
+        self.pending = (0..self.cells.len()).collect();
+        self.dirty.fill(true);
+    }
+
+    /// Sets cell `i`, noting it for the next flush if it changed.
+    fn set(&mut self, i: usize, cell: Cell) {
+        if self.cells[i] != cell {
+            self.cells[i] = cell;
+            if !self.dirty[i] {
+                self.dirty[i] = true;
+                self.pending.push(i);
+            }
+        }
+    }
+
+    pub fn set_heatmap(&mut self, heatmap: Option<Heatmap>) {
+        self.heatmap = heatmap;
+    }
+
+    /// The cell for a single space in the current theme, taking the heatmap
+    /// into account.
+    pub fn paint(&self, space: Space) -> Cell {
+        match (self.heatmap, space) {
+            (Some(heatmap), Space::Visited(distance)) => heatmap.cell(distance),
+            _ => self.theme.cell(space),
         }
     }
 
     pub fn put(&mut self, x: usize, y: usize, ch: char, colors: Colors) -> Option<()> {
         let pos = y * self.width + x;
-        if let Some(buffer_cell) = self.cells.get_mut(pos) {
-            *buffer_cell = Cell { ch, colors };
+        if pos < self.cells.len() {
+            self.set(pos, Cell { ch, colors });
             Some(())
         } else {
             None
@@ -109,46 +401,222 @@ impl Buffer {
     }
 
     pub fn puts(&mut self, x: usize, y: usize, chars: &[char], colors: Colors) -> Option<()> {
-        let start = y * self.width + x;
-        if start + chars.len() > self.width || y > self.height {
+        if y >= self.height || x + chars.len() > self.width {
             return None;
         }
-        for (cell, &ch) in self.cells.iter_mut().zip(chars.iter()) {
-            *cell = Cell { ch, colors }
+        let start = y * self.width + x;
+        for (i, &ch) in chars.iter().enumerate() {
+            self.set(start + i, Cell { ch, colors });
         }
         Some(())
     }
 
-    pub fn flush(&self, wrte: &mut impl io::Write) -> io::Result<()> {
-        wrte.queue(Clear(ClearType::All))?;
-        for &Cell { ch, colors } in self.cells.iter() {
-            let styled_content = style(ch);
-            wrte.queue(SetColors(colors))?;
-            wrte.queue(PrintStyledContent(styled_content))?;
-            wrte.queue(ResetColor)?;
+    /// Writes `text` from `x`, `y`, cutting it off at the right edge instead of
+    /// failing like [`Buffer::puts`]. Returns the column after the last
+    /// character.
+    pub fn put_str(&mut self, x: usize, y: usize, text: &str, colors: Colors) -> usize {
+        let mut end = x;
+        for ch in text.chars() {
+            if end >= self.width || self.put(end, y, ch, colors).is_none() {
+                break;
+            }
+            end += 1;
+        }
+        end
+    }
+
+    /// Blanks row `y` with `colors`.
+    pub fn clear_row(&mut self, y: usize, colors: Colors) {
+        if y < self.height {
+            for i in y * self.width..(y + 1) * self.width {
+                self.set(i, Cell { ch: ' ', colors });
+            }
+        }
+    }
+
+    /// Draws the part of `grid` the viewport shows, blanking any of the
+    /// viewport the grid doesn't cover. Cells outside the viewport are left
+    /// alone.
+    pub fn draw_grid(&mut self, grid: &Grid) {
+        for y in 0..self.viewport.height {
+            for x in 0..self.viewport.width {
+                self.draw_grid_cell(grid, x, y);
+            }
+        }
+    }
+
+    /// Redraws the cell showing grid `point`, if it is in the viewport, after
+    /// that space changed.
+    pub fn draw_point(&mut self, grid: &Grid, point: Point) {
+        let (x, y) = self.content_point(point);
+        let x = x as isize - self.viewport.x;
+        let y = y as isize - self.viewport.y;
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) {
+            if x < self.viewport.width && y < self.viewport.height {
+                self.draw_grid_cell(grid, x, y);
+            }
+        }
+    }
+
+    /// Draws an arrow over each frontier and visited space in the viewport,
+    /// pointing at the space it was reached from, with `preds` holding those
+    /// by index. Arrows need a character per space, so none are drawn
+    /// zoomed out or in half-block mode.
+    pub fn draw_arrows(&mut self, grid: &Grid, preds: &[Option<Point>]) {
+        if self.mode != RenderMode::Full || self.viewport.zoom > 1 {
+            return;
+        }
+        for y in 0..self.viewport.height {
+            for x in 0..self.viewport.width {
+                let Some((grid_x, grid_y)) = self.viewport.to_grid(x, y) else {
+                    continue;
+                };
+                let point = Point::new(grid_x, grid_y);
+                let Some(space @ (Space::Frontier(_) | Space::Visited(_))) = grid.get(point) else {
+                    continue;
+                };
+                if let Some(&Some(pred)) = preds.get(point.index(grid.width)) {
+                    let cell = Cell {
+                        ch: arrow(point, pred),
+                        ..self.paint(space)
+                    };
+                    self.set(y * self.width + x, cell);
+                }
+            }
         }
-        wrte.flush()?;
-        Ok(())
     }
 
+    /// Draws viewport position `x`, `y`, which must be inside the viewport.
+    fn draw_grid_cell(&mut self, grid: &Grid, x: usize, y: usize) {
+        let zoom = self.viewport.zoom;
+        let space = |x, y| viewport::sample(grid, zoom, x, y);
+        let cell = self
+            .viewport
+            .to_grid(x, y)
+            .and_then(|(grid_x, grid_y)| {
+                self.mode
+                    .cell(grid_x, grid_y, space, |space| self.paint(space))
+            })
+            .unwrap_or_default();
+        self.set(y * self.width + x, cell);
+    }
+
+    /// Draws `grid` and writes every cell that differs from what is on screen.
     pub fn flush_diff(&mut self, wrte: &mut impl io::Write, grid: &Grid) -> io::Result<()> {
-        for (i, (space, cell)) in grid.spaces.iter().zip(self.cells.iter_mut()).enumerate() {
-            let x = (i % grid.width).try_into().unwrap();
-            let y = (i / grid.width).try_into().unwrap();
-            let new_cell = Cell::from(space);
-            if new_cell != *cell {
-                *cell = new_cell;
-                let (ch, colors) = (cell.ch, cell.colors);
-                let styled_content = style(ch);
-                wrte.queue(MoveTo(x, y))?;
-                wrte.queue(SetColors(colors))?;
-                wrte.queue(PrintStyledContent(styled_content))?;
-                wrte.queue(ResetColor)?;
+        self.draw_grid(grid);
+        self.flush_changes(wrte)
+    }
+
+    /// Writes every cell that differs from what is on screen, for when the
+    /// grid was drawn with [`Buffer::draw_grid`] and something put on top.
+    /// Only the cells drawn since the last flush are looked at.
+    pub fn flush_changes(&mut self, wrte: &mut impl io::Write) -> io::Result<()> {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.sort_unstable();
+        let mut cells = CellWriter::new(wrte);
+        for &i in &pending {
+            self.dirty[i] = false;
+            if self.cells[i] != self.screen[i] {
+                self.screen[i] = self.cells[i];
+                let (x, y) = ((i % self.width) as u16, (i / self.width) as u16);
+                let at = (self.origin.0 + x, self.origin.1 + y);
+                if let Err(err) = cells.put(Some(at), self.depth.downsample_cell(self.cells[i])) {
+                    // What made it to the terminal is anyone's guess now.
+                    self.invalidate();
+                    return Err(err);
+                }
+            }
+        }
+        pending.clear();
+        self.pending = pending;
+        cells.finish()
+    }
+}
+
+/// The arrow pointing from `point` at its neighbour `to`.
+fn arrow(point: Point, to: Point) -> char {
+    match (to.x.cmp(&point.x), to.y.cmp(&point.y)) {
+        (Ordering::Less, _) => '←',
+        (Ordering::Greater, _) => '→',
+        (_, Ordering::Less) => '↑',
+        _ => '↓',
+    }
+}
+
+/// Writes cells to the terminal with as few escape sequences as it can: the
+/// cursor is only moved when the next cell isn't where the last one left it,
+/// colours are only set when they change, and the characters in between go
+/// out together.
+struct CellWriter<'a, W: io::Write> {
+    wrte: &'a mut W,
+    /// Where the cursor is, if known.
+    cursor: Option<(u16, u16)>,
+    /// The colours the terminal is set to. A flush starts and ends on the
+    /// terminal's own colours.
+    colors: Colors,
+    /// Characters waiting to be printed at the cursor.
+    run: String,
+}
+
+impl<'a, W: io::Write> CellWriter<'a, W> {
+    const RESET: Colors = Colors {
+        foreground: None,
+        background: None,
+    };
+
+    fn new(wrte: &'a mut W) -> Self {
+        Self {
+            wrte,
+            cursor: None,
+            colors: Self::RESET,
+            run: String::new(),
+        }
+    }
+
+    /// Writes `cell` at screen position `at`, or wherever the cursor is for
+    /// `None`.
+    fn put(&mut self, at: Option<(u16, u16)>, cell: Cell) -> io::Result<()> {
+        if let Some(at) = at {
+            if self.cursor != Some(at) {
+                self.end_run()?;
+                self.wrte.queue(MoveTo(at.0, at.1))?;
+                self.cursor = Some(at);
+            }
+        }
+        if cell.colors != self.colors {
+            self.end_run()?;
+            // Setting a colour to `None` does nothing, so going back to the
+            // terminal's own colour takes a reset.
+            let old = self.colors;
+            if (old.foreground.is_some() && cell.colors.foreground.is_none())
+                || (old.background.is_some() && cell.colors.background.is_none())
+            {
+                self.wrte.queue(ResetColor)?;
             }
+            self.wrte.queue(SetColors(cell.colors))?;
+            self.colors = cell.colors;
         }
-        wrte.flush()?;
+        self.run.push(cell.ch);
+        self.cursor = self.cursor.map(|(x, y)| (x + 1, y));
         Ok(())
     }
+
+    fn end_run(&mut self) -> io::Result<()> {
+        if !self.run.is_empty() {
+            self.wrte.queue(Print(&self.run))?;
+            self.run.clear();
+        }
+        Ok(())
+    }
+
+    /// Writes what is left, puts the colours back and flushes.
+    fn finish(mut self) -> io::Result<()> {
+        self.end_run()?;
+        if self.colors != Self::RESET {
+            self.wrte.queue(ResetColor)?;
+        }
+        self.wrte.flush()
+    }
 }
 
 // Assignment4_Tests Buffer
@@ -192,6 +660,40 @@ mod buffer_tests {
     }
 }
 
+#[cfg(test)]
+mod text_tests {
+    use crossterm::style::{Color, Colors};
+
+    use super::{Buffer, Cell};
+
+    #[test]
+    fn manual_test_puts_at_position() {
+        let mut buf = Buffer::new(6, 3);
+        let colors = Colors::new(Color::Cyan, Color::Magenta);
+
+        assert_eq!(Some(()), buf.puts(2, 1, &['a', 'b', 'c'], colors));
+        let row: Vec<char> = buf.cells[6..12].iter().map(|cell| cell.ch).collect();
+        assert_eq!(vec![' ', ' ', 'a', 'b', 'c', ' '], row);
+        assert_eq!(Cell::default(), buf.cells[0]);
+
+        // Text that would run off the row or the buffer is refused.
+        assert_eq!(None, buf.puts(4, 0, &['a', 'b', 'c'], colors));
+        assert_eq!(None, buf.puts(0, 3, &['a'], colors));
+        assert_eq!(' ', buf.cells[4].ch);
+    }
+
+    #[test]
+    fn manual_test_put_str_clips() {
+        let mut buf = Buffer::new(4, 2);
+        let colors = Cell::default().colors;
+
+        assert_eq!(4, buf.put_str(1, 1, "hello", colors));
+        let row: String = buf.cells[4..].iter().map(|cell| cell.ch).collect();
+        assert_eq!(" hel", row);
+        assert_eq!(2, buf.put_str(2, 5, "hi", colors));
+    }
+}
+
 // Assignment4_Tests Buffer
 #[cfg(test)]
 mod input_space_partioning_buffer {
@@ -216,7 +718,7 @@ mod input_space_partioning_buffer {
         assert!(result.is_some());
     }
 
-    // T F - Inviable 
+    // T F - Inviable
 
     // T T
     #[test]
@@ -273,3 +775,263 @@ mod cell_tests {
             .for_each(|value| assert_ne!(None, value));
     }
 }
+
+#[cfg(test)]
+mod half_block_tests {
+    use crossterm::style::{Color, Colors};
+
+    use super::{Buffer, Cell, RenderMode};
+    use crate::{grid::Grid, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_half_block_cell() {
+        let obstacle = Cell::from(Space::Obstacle);
+        let empty = Cell::from(Space::Empty);
+
+        let both = Cell::half_block(obstacle, Cell::from(Space::Path));
+        assert_eq!('▀', both.ch);
+        assert_eq!(
+            Colors {
+                foreground: Some(Color::DarkRed),
+                background: Some(Color::AnsiValue(7)),
+            },
+            both.colors
+        );
+
+        let bottom_only = Cell::half_block(empty, obstacle);
+        assert_eq!('▄', bottom_only.ch);
+        assert_eq!(Some(Color::DarkRed), bottom_only.colors.foreground);
+        assert_eq!(None, bottom_only.colors.background);
+
+        assert_eq!(' ', Cell::half_block(empty, empty).ch);
+    }
+
+    #[test]
+    fn manual_test_half_block_flush_diff() {
+        // Three grid rows fit in two terminal rows; the last one has no partner.
+        let mut grid = Grid::new(2, 3);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(1, 1)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(0, 2)).unwrap() = Space::Obstacle;
+
+        let height = grid.height.div_ceil(RenderMode::HalfBlock.rows_per_cell());
+        let mut buf = Buffer::with_mode(2, height, RenderMode::HalfBlock);
+        buf.flush_diff(&mut Vec::new(), &grid).unwrap();
+
+        let chars: Vec<char> = buf.cells.iter().map(|cell| cell.ch).collect();
+        assert_eq!(vec!['▀', '▄', '▀', ' '], chars);
+    }
+}
+
+#[cfg(test)]
+mod viewport_buffer_tests {
+    use super::{Buffer, Cell};
+    use crate::{grid::Grid, point::Point, space::Space, viewport::Viewport};
+
+    #[test]
+    fn manual_test_flush_only_changed_cells() {
+        let mut buf = Buffer::new(4, 2);
+        let mut out = Vec::new();
+        buf.flush_changes(&mut out).unwrap();
+        assert!(!out.is_empty());
+
+        // Nothing drawn since the last flush means nothing written.
+        out.clear();
+        buf.clear_row(1, Cell::default().colors);
+        buf.flush_changes(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        buf.put(3, 1, 'x', Cell::default().colors);
+        buf.flush_changes(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains('x'));
+    }
+
+    #[test]
+    fn manual_test_flush_coalesces() {
+        let colors = Cell::default().colors;
+        let mut buf = Buffer::new(6, 2);
+        buf.flush_changes(&mut Vec::new()).unwrap();
+
+        // A run of cells in one colour is one move, one colour change and
+        // one print, and the gap before the last cell takes another move.
+        buf.put_str(1, 1, "abc", colors);
+        buf.put(5, 1, 'd', colors);
+        let mut out = Vec::new();
+        buf.flush_changes(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!("\x1b[2;2H\x1b[38;5;15mabc\x1b[2;6Hd\x1b[0m", out);
+    }
+
+    #[test]
+    fn manual_test_draw_point() {
+        let mut grid = Grid::new(6, 2);
+        let mut buf = Buffer::new(4, 2);
+        buf.set_viewport(Viewport::centred(4, 2, 6, 2));
+        buf.draw_grid(&grid);
+
+        *grid.get_mut(Point::new(2, 1)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Obstacle;
+        buf.draw_point(&grid, Point::new(2, 1));
+        buf.draw_point(&grid, Point::new(0, 0));
+
+        // Only the point inside the viewport is drawn.
+        let obstacle = buf.paint(Space::Obstacle);
+        assert_eq!(obstacle, buf.cells[5]);
+        assert_eq!(
+            1,
+            buf.cells.iter().filter(|&&cell| cell == obstacle).count()
+        );
+    }
+
+    #[test]
+    fn manual_test_draw_arrows() {
+        // S o o
+        // . + .
+        let mut grid = Grid::new(3, 2);
+        let start = Point::new(0, 0);
+        *grid.get_mut(start).unwrap() = Space::Start(start);
+        *grid.get_mut(Point::new(1, 0)).unwrap() = Space::Visited(1);
+        *grid.get_mut(Point::new(2, 0)).unwrap() = Space::Visited(2);
+        *grid.get_mut(Point::new(1, 1)).unwrap() = Space::Frontier(2);
+        let mut preds = vec![None; 6];
+        preds[1] = Some(start);
+        preds[2] = Some(Point::new(1, 0));
+        preds[4] = Some(Point::new(1, 0));
+        // A predecessor left over from a space that has since been cleared.
+        preds[3] = Some(start);
+
+        let mut buf = Buffer::new(3, 2);
+        buf.set_viewport(Viewport::centred(3, 2, 3, 2));
+        buf.draw_grid(&grid);
+        buf.draw_arrows(&grid, &preds);
+        let glyphs: String = buf.cells.iter().map(|cell| cell.ch).collect();
+        assert_eq!(" ←← ↑ ", glyphs);
+        assert_eq!(buf.paint(Space::Frontier(2)).colors, buf.cells[4].colors);
+
+        // Zoomed out there is no room for them.
+        buf.set_zoom(2, &grid);
+        buf.draw_grid(&grid);
+        buf.draw_arrows(&grid, &preds);
+        assert!(buf.cells.iter().all(|cell| !"←↑".contains(cell.ch)));
+    }
+
+    #[test]
+    fn manual_test_draw_grid_centred() {
+        let mut grid = Grid::new(2, 1);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Obstacle;
+
+        let mut buf = Buffer::new(4, 3);
+        buf.put(0, 2, 'x', Cell::default().colors);
+        buf.set_viewport(Viewport::centred(4, 2, 2, 1));
+        buf.draw_grid(&grid);
+
+        let obstacle = buf.paint(Space::Obstacle);
+        let cells: Vec<bool> = buf.cells.iter().map(|cell| *cell == obstacle).collect();
+        assert_eq!(
+            vec![false, true, false, false, false, false, false, false, false, false, false, false],
+            cells
+        );
+        // The row under the viewport is not touched.
+        assert_eq!('x', buf.cells[8].ch);
+    }
+
+    #[test]
+    fn manual_test_draw_grid_zoomed() {
+        // # # . .
+        // # . . .
+        // . . . E
+        let mut grid = Grid::new(4, 3);
+        for point in [Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)] {
+            *grid.get_mut(point).unwrap() = Space::Obstacle;
+        }
+        let end = Point::new(3, 2);
+        *grid.get_mut(end).unwrap() = Space::End(end);
+
+        let mut buf = Buffer::new(2, 2);
+        buf.set_zoom(2, &grid);
+        assert_eq!((2, 2), buf.content_size(&grid));
+        buf.draw_grid(&grid);
+
+        let expected = [Space::Obstacle, Space::Empty, Space::Empty, Space::End(end)];
+        let expected: Vec<Cell> = expected.into_iter().map(|space| buf.paint(space)).collect();
+        assert_eq!(expected, buf.cells);
+    }
+
+    #[test]
+    fn manual_test_follow_scrolls() {
+        let grid = Grid::new(40, 10);
+        let mut buf = Buffer::new(8, 4);
+        buf.set_viewport(Viewport::centred(8, 4, 40, 10));
+
+        buf.follow(Point::new(20, 5), &grid);
+        assert_eq!((16, 3), (buf.viewport().x, buf.viewport().y));
+        // Staying near the middle doesn't move the view.
+        buf.follow(Point::new(21, 5), &grid);
+        assert_eq!((16, 3), (buf.viewport().x, buf.viewport().y));
+
+        buf.follow(Point::new(39, 9), &grid);
+        assert_eq!((32, 6), (buf.viewport().x, buf.viewport().y));
+    }
+
+    #[test]
+    fn manual_test_viewport_clipped_to_buffer() {
+        let mut buf = Buffer::new(3, 2);
+        buf.set_viewport(Viewport::centred(10, 10, 1, 1));
+        assert_eq!((3, 2), (buf.viewport().width, buf.viewport().height));
+        buf.draw_grid(&Grid::new(1, 1));
+    }
+}
+
+#[cfg(test)]
+mod heatmap_tests {
+    use crossterm::style::Color;
+
+    use super::{Buffer, Cell, Heatmap};
+    use crate::space::Space;
+    use crate::theme::ThemeKind;
+
+    #[test]
+    fn manual_test_heatmap_ends() {
+        let heatmap = Heatmap { span: 10 };
+
+        assert_eq!(
+            Color::Rgb {
+                r: 48,
+                g: 64,
+                b: 200
+            },
+            heatmap.color(0)
+        );
+        assert_eq!(
+            Color::Rgb {
+                r: 220,
+                g: 40,
+                b: 30
+            },
+            heatmap.color(10)
+        );
+        // Past the span stays at the last colour.
+        assert_eq!(heatmap.color(10), heatmap.color(500));
+        assert_ne!(heatmap.color(3), heatmap.color(4));
+    }
+
+    #[test]
+    fn manual_test_paint_visited() {
+        let mut buf = Buffer::new(1, 1);
+        assert_eq!(Cell::from(Space::Visited(3)), buf.paint(Space::Visited(3)));
+
+        buf.set_theme(ThemeKind::Monochrome.theme());
+        assert_eq!(
+            ThemeKind::Monochrome.theme().visited,
+            buf.paint(Space::Visited(3))
+        );
+
+        let heatmap = Heatmap { span: 10 };
+        buf.set_heatmap(Some(heatmap));
+        assert_eq!(heatmap.cell(3), buf.paint(Space::Visited(3)));
+        assert_eq!(
+            ThemeKind::Monochrome.theme().obstacle,
+            buf.paint(Space::Obstacle)
+        );
+    }
+}
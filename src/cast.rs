@@ -0,0 +1,129 @@
+use std::{
+    io::{self, Write},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Passes everything written through to `inner` and records it in asciicast v2
+/// format to `cast`, so the session can be replayed with asciinema. Writes are
+/// gathered into one output event per flush, so each flushed frame gets its
+/// own timestamp.
+pub struct Recorder<W: Write, C: Write> {
+    inner: W,
+    cast: C,
+    started: Instant,
+    frame: Vec<u8>,
+}
+
+impl<W: Write, C: Write> Recorder<W, C> {
+    /// Starts a recording of a `width` by `height` terminal, writing the
+    /// header to `cast`.
+    pub fn new(inner: W, mut cast: C, width: u16, height: u16) -> io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        writeln!(
+            cast,
+            r#"{{"version": 2, "width": {width}, "height": {height}, "timestamp": {timestamp}}}"#
+        )?;
+        Ok(Self {
+            inner,
+            cast,
+            started: Instant::now(),
+            frame: Vec::new(),
+        })
+    }
+
+    /// Records that the terminal is now `width` by `height`.
+    pub fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        self.event("r", &format!("{width}x{height}"))
+    }
+
+    /// The terminal being recorded, for output the recording should leave
+    /// out.
+    pub fn terminal(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(self.cast, "[{time:.6}, \"{code}\", {}]", json_string(data))
+    }
+}
+
+impl<W: Write, C: Write> Write for Recorder<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.frame.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        if !self.frame.is_empty() {
+            let frame = String::from_utf8_lossy(&self.frame).into_owned();
+            self.frame.clear();
+            self.event("o", &frame)?;
+        }
+        self.cast.flush()
+    }
+}
+
+/// `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if ch.is_control() => json.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod cast_tests {
+    use std::io::Write;
+
+    use super::{json_string, Recorder};
+
+    #[test]
+    fn manual_test_json_string() {
+        assert_eq!(r#""a\"b\\c""#, json_string(r#"a"b\c"#));
+        assert_eq!(r#""\u001b[2;3H▀\n""#, json_string("\x1b[2;3H▀\n"));
+    }
+
+    #[test]
+    fn manual_test_record_frames() {
+        let mut terminal = Vec::new();
+        let mut cast = Vec::new();
+        let mut recorder = Recorder::new(&mut terminal, &mut cast, 80, 24).unwrap();
+        recorder.write_all(b"\x1b[1;1H").unwrap();
+        recorder.write_all(b"ab").unwrap();
+        recorder.flush().unwrap();
+        // Flushing with nothing written records nothing.
+        recorder.flush().unwrap();
+        recorder.resize(100, 30).unwrap();
+        // Straight to the terminal, and left out of the recording.
+        recorder
+            .terminal()
+            .write_all(b"\x1b]52;c;eA==\x07")
+            .unwrap();
+        recorder.flush().unwrap();
+        drop(recorder);
+
+        assert_eq!(b"\x1b[1;1Hab\x1b]52;c;eA==\x07".as_slice(), terminal);
+        let cast = String::from_utf8(cast).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with(r#"{"version": 2, "width": 80, "height": 24, "timestamp": "#));
+        assert!(lines[1].starts_with('[') && lines[1].ends_with(r#", "o", "\u001b[1;1Hab"]"#));
+        assert!(lines[2].ends_with(r#", "r", "100x30"]"#));
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use pathrs::buffer::RenderMode;
+use pathrs::color_depth::ColorDepth;
+use pathrs::maze::{GeneratorKind, SolverKind};
+use pathrs::theme::ThemeKind;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Generate random mazes and watch them get solved")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Defaults to `run` when omitted.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Generate and solve mazes in the terminal until `q` is pressed, or print
+    /// them to stdout with `--headless`. Each solved maze waits for `n` unless
+    /// `--loop` is given.
+    Run(RunArgs),
+    /// Solve a maze file and print the path.
+    Solve {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t)]
+        solver: SolverKind,
+    },
+    /// Generate a maze and write it to a file.
+    Generate {
+        file: PathBuf,
+        #[command(flatten)]
+        maze: MazeArgs,
+    },
+    /// Generate and solve a maze and save a picture of it. The format follows
+    /// the file's extension: `.ppm`, `.svg`, `.png` with the `png` feature, or
+    /// an animation of the whole solve as `.gif` with the `gif` feature.
+    Export {
+        file: PathBuf,
+        #[command(flatten)]
+        maze: MazeArgs,
+        #[arg(long, value_enum, default_value_t)]
+        solver: SolverKind,
+        /// Pixels along each side of a space.
+        #[arg(
+            long,
+            default_value_t = 8,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=256)
+        )]
+        scale: usize,
+        /// Solver steps between frames of a GIF: each step expands one space
+        /// or adds one to the path. Raised for long solves so the GIF has at
+        /// most 300 frames.
+        #[arg(long, default_value_t = 10)]
+        every: usize,
+        /// Milliseconds each frame of a GIF is shown for.
+        #[arg(long, default_value_t = 40)]
+        delay: u64,
+        /// Shade the spaces the solver visited in an SVG.
+        #[arg(long)]
+        shade_visited: bool,
+    },
+    /// Carve a thin-wall maze and print it with box-drawing characters.
+    Walls {
+        /// Width in cells.
+        #[arg(
+            long,
+            default_value_t = 16,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=1024)
+        )]
+        width: usize,
+        /// Height in cells.
+        #[arg(
+            long,
+            default_value_t = 8,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=1024)
+        )]
+        height: usize,
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Also solve the maze and save it to this file as SVG.
+        #[arg(long)]
+        svg: Option<PathBuf>,
+        /// Shade the cells the solver visited in the SVG.
+        #[arg(long, requires = "svg")]
+        shade_visited: bool,
+    },
+    /// Generate and solve many mazes and print statistics.
+    Bench {
+        /// Number of mazes to solve.
+        #[arg(long, short = 'n', default_value_t = 100)]
+        count: usize,
+        #[command(flatten)]
+        maze: MazeArgs,
+        #[arg(long, value_enum, default_value_t)]
+        solver: SolverKind,
+        /// Also replay each solve into an off-screen buffer and report the
+        /// bytes a frame takes to write to the terminal.
+        #[arg(long)]
+        frames: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub maze: MazeArgs,
+    #[arg(long, value_enum, default_value_t)]
+    pub solver: SolverKind,
+    /// Race these solvers side by side on copies of the same maze instead of
+    /// running `--solver`, e.g. `--race bfs,dfs,a-star`. Takes two or more.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub race: Vec<SolverKind>,
+    /// How grid spaces are drawn on terminal characters.
+    #[arg(long, value_enum, default_value_t)]
+    pub render: RenderMode,
+    /// Colour theme, cycled with `t` while running.
+    #[arg(long, value_enum, default_value_t)]
+    pub theme: ThemeKind,
+    /// Theme file mapping each space to a glyph, foreground and background.
+    /// Takes precedence over `--theme`.
+    #[arg(long)]
+    pub theme_file: Option<PathBuf>,
+    /// Colours the terminal can show. Detected from NO_COLOR, COLORTERM and TERM
+    /// when omitted; `no-color` switches to the ascii theme.
+    #[arg(long, value_enum)]
+    pub color_depth: Option<ColorDepth>,
+    /// Colour visited spaces by their distance from the start.
+    #[arg(long)]
+    pub heatmap: bool,
+    /// Frames drawn per second at most, however fast the solver goes.
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u32).range(1..=1000))]
+    pub fps: u32,
+    /// Show the actual frame rate, frame time and bytes per frame over the
+    /// grid.
+    #[arg(long)]
+    pub frame_stats: bool,
+    /// Record the session to this asciicast v2 file, for replaying with
+    /// asciinema.
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+    /// Move on to a new maze a few seconds after each one is solved, or each
+    /// race is over. Without it a finished maze stays up, to be replayed,
+    /// until `n`, `r` or `q` is pressed.
+    #[arg(long = "loop")]
+    pub loop_mazes: bool,
+    /// Print results to stdout instead of animating them. Implied when stdout is
+    /// not a terminal.
+    #[arg(long)]
+    pub headless: bool,
+    /// Number of mazes to generate in headless mode.
+    #[arg(long, short = 'n', default_value_t = 1)]
+    pub count: usize,
+    /// Print each headless run as a line of JSON.
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Options shared by every subcommand that builds a maze.
+#[derive(Debug, Args)]
+pub struct MazeArgs {
+    #[arg(long, value_enum, default_value_t)]
+    pub generator: GeneratorKind,
+    /// Seed for the first maze, random when omitted. Each later maze, in a
+    /// headless or bench batch or after `n`, takes the next seed up.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Grid width, defaults to the terminal width. Required without a terminal.
+    #[arg(long)]
+    pub width: Option<usize>,
+    /// Grid height, defaults to the terminal height. Required without a terminal.
+    #[arg(long)]
+    pub height: Option<usize>,
+}
@@ -0,0 +1,242 @@
+use crossterm::style::{Color, Colors};
+
+use crate::buffer::Cell;
+
+/// How many colours the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ColorDepth {
+    /// 24-bit RGB.
+    TrueColor,
+    /// The xterm 256-colour palette.
+    Ansi256,
+    /// The 16 basic named colours.
+    Ansi16,
+    /// No colour at all, glyphs only.
+    NoColor,
+}
+
+/// The 16 named colours with the RGB values xterm uses for them.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (205, 0, 0)),
+    (Color::DarkGreen, (0, 205, 0)),
+    (Color::DarkYellow, (205, 205, 0)),
+    (Color::DarkBlue, (0, 0, 238)),
+    (Color::DarkMagenta, (205, 0, 205)),
+    (Color::DarkCyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (92, 92, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel levels of the 6x6x6 colour cube in the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Works out the colour depth from `NO_COLOR`, `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::from_env(
+            var("NO_COLOR").as_deref(),
+            var("COLORTERM").as_deref(),
+            var("TERM").as_deref(),
+        )
+    }
+
+    /// The detection rules behind [`ColorDepth::detect`]: a non-empty
+    /// `NO_COLOR` always wins (see no-color.org), then `COLORTERM` advertising
+    /// 24-bit colour, then hints in `TERM`.
+    pub fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::NoColor;
+        }
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        match term {
+            Some("dumb") => ColorDepth::NoColor,
+            Some(term) if term.ends_with("-direct") => ColorDepth::TrueColor,
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+
+    /// The closest colour this depth can show, or `None` for no colour.
+    pub fn downsample(&self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (ColorDepth::NoColor, _) => None,
+            (ColorDepth::TrueColor, _) => Some(color),
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }) => {
+                Some(Color::AnsiValue(rgb_to_ansi256((r, g, b))))
+            }
+            (ColorDepth::Ansi16, Color::Rgb { r, g, b }) => Some(nearest_ansi16((r, g, b))),
+            (ColorDepth::Ansi16, Color::AnsiValue(value)) => {
+                Some(nearest_ansi16(ansi256_to_rgb(value)))
+            }
+            (_, color) => Some(color),
+        }
+    }
+
+    pub fn downsample_cell(&self, cell: Cell) -> Cell {
+        Cell {
+            ch: cell.ch,
+            colors: Colors {
+                foreground: cell.colors.foreground.and_then(|c| self.downsample(c)),
+                background: cell.colors.background.and_then(|c| self.downsample(c)),
+            },
+        }
+    }
+}
+
+/// The RGB value `color` shows as, with named and palette colours as xterm
+/// draws them, or `None` for the terminal's own colour.
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb { r, g, b } => Some((r, g, b)),
+        Color::AnsiValue(value) => Some(ansi256_to_rgb(value)),
+        named => ANSI16
+            .iter()
+            .find(|(candidate, _)| *candidate == named)
+            .map(|&(_, rgb)| rgb),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+        .map(|(color, _)| *color)
+        .unwrap()
+}
+
+fn ansi256_to_rgb(value: u8) -> (u8, u8, u8) {
+    match value {
+        0..=15 => ANSI16[value as usize].1,
+        16..=231 => {
+            let i = value - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let grey = 8 + (value - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+/// Picks whichever is closer of the nearest colour-cube entry and the nearest
+/// step on the grey ramp.
+fn rgb_to_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap() as u8
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance(rgb, ansi256_to_rgb(grey)) < distance(rgb, ansi256_to_rgb(cube)) {
+        grey
+    } else {
+        cube
+    }
+}
+
+#[cfg(test)]
+mod color_depth_tests {
+    use crossterm::style::{Color, Colors};
+
+    use super::ColorDepth;
+    use crate::buffer::Cell;
+
+    #[test]
+    fn manual_test_from_env() {
+        use ColorDepth::*;
+        let cases = [
+            (
+                Some("1"),
+                Some("truecolor"),
+                Some("xterm-256color"),
+                NoColor,
+            ),
+            (Some(""), Some("truecolor"), None, TrueColor),
+            (None, Some("24bit"), Some("xterm"), TrueColor),
+            (None, None, Some("xterm-direct"), TrueColor),
+            (None, None, Some("screen-256color"), Ansi256),
+            (None, None, Some("xterm"), Ansi16),
+            (None, None, Some("dumb"), NoColor),
+            (None, None, None, Ansi16),
+        ];
+        for (no_color, colorterm, term, expected) in cases {
+            assert_eq!(expected, ColorDepth::from_env(no_color, colorterm, term));
+        }
+    }
+
+    #[test]
+    fn manual_test_downsample() {
+        let orange = Color::Rgb {
+            r: 255,
+            g: 135,
+            b: 0,
+        };
+        assert_eq!(Some(orange), ColorDepth::TrueColor.downsample(orange));
+        assert_eq!(
+            Some(Color::AnsiValue(208)),
+            ColorDepth::Ansi256.downsample(orange)
+        );
+        assert_eq!(
+            Some(Color::DarkYellow),
+            ColorDepth::Ansi16.downsample(orange)
+        );
+        assert_eq!(None, ColorDepth::NoColor.downsample(orange));
+
+        let grey = Color::Rgb {
+            r: 128,
+            g: 128,
+            b: 128,
+        };
+        assert_eq!(
+            Some(Color::AnsiValue(244)),
+            ColorDepth::Ansi256.downsample(grey)
+        );
+
+        assert_eq!(
+            Some(Color::Grey),
+            ColorDepth::Ansi16.downsample(Color::AnsiValue(7))
+        );
+        assert_eq!(
+            Some(Color::DarkRed),
+            ColorDepth::Ansi16.downsample(Color::DarkRed)
+        );
+    }
+
+    #[test]
+    fn manual_test_downsample_cell_keeps_glyph() {
+        let cell = Cell {
+            ch: 'o',
+            colors: Colors::new(Color::Rgb { r: 0, g: 0, b: 0 }, Color::DarkRed),
+        };
+        let actual = ColorDepth::NoColor.downsample_cell(cell);
+        assert_eq!('o', actual.ch);
+        assert_eq!(None, actual.colors.foreground);
+        assert_eq!(None, actual.colors.background);
+    }
+}
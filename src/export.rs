@@ -0,0 +1,180 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::grid::Grid;
+#[cfg(feature = "gif")]
+use crate::image::Animation;
+use crate::image::Image;
+use crate::maze::{GeneratorKind, Progress, Solution, SolverKind};
+use crate::point::Point;
+use crate::svg::Svg;
+use crate::wall_maze::WallMaze;
+
+/// The image formats a solved maze can be saved as, picked by a file's
+/// extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Svg,
+    #[cfg(feature = "png")]
+    Png,
+    #[cfg(feature = "gif")]
+    Gif,
+}
+
+impl Format {
+    /// The format `file`'s extension names. Fails for extensions that aren't
+    /// an image format, or are one this build leaves out.
+    pub fn of(file: &Path) -> io::Result<Self> {
+        match file.extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => Ok(Format::Ppm),
+            Some("svg") => Ok(Format::Svg),
+            #[cfg(feature = "png")]
+            Some("png") => Ok(Format::Png),
+            #[cfg(not(feature = "png"))]
+            Some("png") => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "PNG export needs the `png` feature",
+            )),
+            #[cfg(feature = "gif")]
+            Some("gif") => Ok(Format::Gif),
+            #[cfg(not(feature = "gif"))]
+            Some("gif") => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "GIF export needs the `gif` feature",
+            )),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "can't tell the image format of {}, name it .ppm, .svg, .png or .gif",
+                    file.display()
+                ),
+            )),
+        }
+    }
+}
+
+/// Builds and solves a maze, then saves a picture of it.
+pub struct Export {
+    pub generator: GeneratorKind,
+    pub solver: SolverKind,
+    pub width: usize,
+    pub height: usize,
+    /// Pixels along each side of a space.
+    pub scale: usize,
+    /// Solver steps between frames of a GIF.
+    pub every: usize,
+    /// How long each frame of a GIF is shown for.
+    pub delay: Duration,
+    /// Shade the spaces the solver visited in an SVG.
+    pub shade_visited: bool,
+}
+
+impl Export {
+    /// Builds the maze for `seed`, solves it and saves it to `file` in the
+    /// [`Format`] its extension names.
+    pub fn save(&self, file: &Path, seed: u64) -> io::Result<()> {
+        let format = Format::of(file)?;
+        let generated = self
+            .generator
+            .build(Grid::new(self.width, self.height), seed)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "grid is too small for a maze")
+            })?;
+        let progress = Progress::default();
+        // A GIF replays the solve from the grid as it was before it.
+        #[cfg(feature = "gif")]
+        let replay = (generated.grid.lock().unwrap().clone(), progress.subscribe());
+        let solution = self.solver.solve(
+            generated.start,
+            generated.grid.clone(),
+            Duration::ZERO,
+            &progress,
+        );
+
+        // Rendered in full before the file is touched, so a failure leaves
+        // whatever was there before.
+        let mut out = Vec::new();
+        let grid = generated.grid.lock().unwrap();
+        match format {
+            #[cfg(feature = "gif")]
+            Format::Gif => {
+                let (before, events) = replay;
+                let mut animation = Animation::new(&mut out, self.width, self.height, self.scale)?;
+                animation.replay(before, events.try_iter(), self.every, self.delay)?;
+            }
+            Format::Svg => {
+                let svg = Svg::from_grid(&grid, &solution.path, self.shade_visited, self.scale);
+                write!(out, "{svg}")?;
+            }
+            #[cfg(feature = "png")]
+            Format::Png => Image::from_grid(&grid, self.scale)?.write_png(&mut out)?,
+            Format::Ppm => Image::from_grid(&grid, self.scale)?.write_ppm(&mut out)?,
+        }
+        std::fs::write(file, out)
+    }
+}
+
+/// Solves `maze` and saves it to `file` as SVG.
+pub fn save_walls(file: &Path, maze: &WallMaze, shade_visited: bool) -> io::Result<()> {
+    let grid = maze.to_grid();
+    let solution = match (grid.start(), grid.end()) {
+        (Some(start), _) => SolverKind::Bfs.solve(
+            start,
+            Arc::new(Mutex::new(grid)),
+            Duration::ZERO,
+            &Progress::default(),
+        ),
+        // A maze of one cell starts where it ends, and the grid only shows the
+        // end.
+        (None, Some(end)) => Solution {
+            visited: vec![end],
+            path: vec![end],
+        },
+        (None, None) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "maze has no cells",
+            ))
+        }
+    };
+    let cells = |points: &[Point]| -> Vec<Point> {
+        points
+            .iter()
+            .filter_map(|&point| WallMaze::cell_of(point))
+            .collect()
+    };
+    let visited = match shade_visited {
+        true => cells(&solution.visited),
+        false => vec![],
+    };
+    let svg = Svg::from_wall_maze(maze, &cells(&solution.path), &visited, 16);
+    std::fs::write(file, svg.to_string())
+}
+
+#[cfg(test)]
+mod export_tests {
+    use std::io;
+    use std::path::Path;
+
+    use super::Format;
+
+    #[test]
+    fn manual_test_format_of() {
+        assert_eq!(Format::Ppm, Format::of(Path::new("maze.ppm")).unwrap());
+        assert_eq!(Format::Svg, Format::of(Path::new("out/maze.svg")).unwrap());
+        for name in ["maze.jpg", "maze", "maze.PPM.txt"] {
+            let err = Format::of(Path::new(name)).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidInput, err.kind(), "{name}");
+        }
+        #[cfg(not(feature = "png"))]
+        assert_eq!(
+            io::ErrorKind::Unsupported,
+            Format::of(Path::new("maze.png")).unwrap_err().kind()
+        );
+        #[cfg(feature = "gif")]
+        assert_eq!(Format::Gif, Format::of(Path::new("maze.gif")).unwrap());
+    }
+}
@@ -1,5 +1,10 @@
+use std::{fmt, str::FromStr};
+
 use crate::{point::Point, space::Space};
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawGrid"))]
 pub struct Grid {
     pub spaces: Vec<Space>,
     pub width: usize,
@@ -68,12 +73,184 @@ impl Grid {
             })
             .collect::<Vec<_>>()
     }
+
+    pub fn start(&self) -> Option<Point> {
+        self.spaces.iter().find_map(|space| match space {
+            Space::Start(point) => Some(*point),
+            _ => None,
+        })
+    }
+
+    pub fn end(&self) -> Option<Point> {
+        self.spaces.iter().find_map(|space| match space {
+            Space::End(point) => Some(*point),
+            _ => None,
+        })
+    }
+
+    /// Checks that a grid read in is one a maze can be solved on: a space for
+    /// every cell, and one start and one end that know where they are.
+    fn validate(&self) -> Result<(), ParseGridError> {
+        if self.width.checked_mul(self.height) != Some(self.spaces.len()) {
+            return Err(ParseGridError::WrongSize {
+                width: self.width,
+                height: self.height,
+                spaces: self.spaces.len(),
+            });
+        }
+        let (mut starts, mut ends) = (0, 0);
+        for (index, space) in self.spaces.iter().enumerate() {
+            let point = Point::from_index(index, self.width);
+            let (Space::Start(at) | Space::End(at)) = space else {
+                continue;
+            };
+            if *at != point {
+                return Err(ParseGridError::Misplaced { at: *at, point });
+            }
+            match space {
+                Space::Start(_) => starts += 1,
+                _ => ends += 1,
+            }
+        }
+        match (starts, ends) {
+            (1, 1) => Ok(()),
+            _ => Err(ParseGridError::StartsAndEnds { starts, ends }),
+        }
+    }
+}
+
+/// A [`Grid`] as it is written in JSON, before it is checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawGrid {
+    spaces: Vec<Space>,
+    width: usize,
+    height: usize,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawGrid> for Grid {
+    type Error = ParseGridError;
+
+    fn try_from(raw: RawGrid) -> Result<Self, Self::Error> {
+        let grid = Self {
+            spaces: raw.spaces,
+            width: raw.width,
+            height: raw.height,
+        };
+        grid.validate()?;
+        Ok(grid)
+    }
+}
+
+/// Writes the grid in the plain-text maze format, one row per line using
+/// [`Space::to_char`].
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.spaces.chunks(self.width.max(1)) {
+            let line: String = row.iter().map(Space::to_char).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    Empty,
+    RaggedRow {
+        row: usize,
+    },
+    UnknownChar {
+        ch: char,
+        point: Point,
+    },
+    WrongSize {
+        width: usize,
+        height: usize,
+        spaces: usize,
+    },
+    StartsAndEnds {
+        starts: usize,
+        ends: usize,
+    },
+    /// A start or end at `point` that says it is at `at`.
+    Misplaced {
+        at: Point,
+        point: Point,
+    },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "maze is empty"),
+            ParseGridError::RaggedRow { row } => {
+                write!(f, "row {row} has a different width than the first row")
+            }
+            ParseGridError::UnknownChar { ch, point } => {
+                write!(f, "unknown character {ch:?} at {},{}", point.x, point.y)
+            }
+            ParseGridError::WrongSize {
+                width,
+                height,
+                spaces,
+            } => write!(f, "a {width}x{height} maze can't have {spaces} spaces"),
+            ParseGridError::StartsAndEnds { starts, ends } => write!(
+                f,
+                "maze has {starts} starts and {ends} ends, it needs one of each"
+            ),
+            ParseGridError::Misplaced { at, point } => write!(
+                f,
+                "space at {},{} says it is at {},{}",
+                point.x, point.y, at.x, at.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Only the newlines at the end are dropped. An empty row anywhere else
+        // is as ragged as any other short one.
+        let s = s.trim_end_matches(['\n', '\r']);
+        if s.is_empty() {
+            return Err(ParseGridError::Empty);
+        }
+        let lines: Vec<&str> = s.lines().map(|line| line.trim_end_matches('\r')).collect();
+        let width = lines[0].chars().count();
+
+        let mut spaces = Vec::with_capacity(width * lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            if line.is_empty() || line.chars().count() != width {
+                return Err(ParseGridError::RaggedRow { row: y });
+            }
+            for (x, ch) in line.chars().enumerate() {
+                let point = Point::new(x, y);
+                let space =
+                    Space::from_char(ch, point).ok_or(ParseGridError::UnknownChar { ch, point })?;
+                spaces.push(space);
+            }
+        }
+
+        let grid = Self {
+            spaces,
+            width,
+            height: lines.len(),
+        };
+        grid.validate()?;
+        Ok(grid)
+    }
 }
 
 // Assignment4_Tests Grid
 #[cfg(test)]
 mod grid_tests {
-    use super::{Grid, Space};
+    use super::{Grid, ParseGridError, Space};
     use crate::point::Point;
 
     use rand::{thread_rng, Rng};
@@ -114,7 +291,7 @@ mod grid_tests {
         let height = 3;
         let spaces: Vec<_> = vec![
             Space::Empty,
-            Space::Visited,
+            Space::Visited(1),
             Space::Empty,
             Space::Obstacle,
             Space::Empty,
@@ -146,6 +323,74 @@ mod grid_tests {
         assert!(expected.len() == counter)
     }
 
+    #[test]
+    fn manual_test_text_round_trip() {
+        let text = "S.#\n#.E\n";
+        let grid: Grid = text.parse().unwrap();
+
+        assert_eq!(3, grid.width);
+        assert_eq!(2, grid.height);
+        assert_eq!(Some(Point::new(0, 0)), grid.start());
+        assert_eq!(Some(Point::new(2, 1)), grid.end());
+        assert_eq!(text, grid.to_string());
+    }
+
+    #[test]
+    fn manual_test_parse_errors() {
+        assert_eq!(Err(ParseGridError::Empty), "".parse::<Grid>().map(|_| ()));
+        assert_eq!(
+            Err(ParseGridError::RaggedRow { row: 1 }),
+            "S.\n.\n".parse::<Grid>().map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseGridError::RaggedRow { row: 1 }),
+            "S.\n\n.E\n".parse::<Grid>().map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseGridError::RaggedRow { row: 0 }),
+            "\nS.\n.E\n".parse::<Grid>().map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseGridError::Empty),
+            "\n\n".parse::<Grid>().map(|_| ())
+        );
+        assert_eq!(2, "S.\n.E\n\n\r\n".parse::<Grid>().unwrap().height);
+        assert_eq!(
+            Err(ParseGridError::UnknownChar {
+                ch: 'x',
+                point: Point::new(1, 0)
+            }),
+            "Sx\n".parse::<Grid>().map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseGridError::StartsAndEnds { starts: 1, ends: 0 }),
+            "S.\n..\n".parse::<Grid>().map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseGridError::StartsAndEnds { starts: 2, ends: 1 }),
+            "S.\nSE\n".parse::<Grid>().map(|_| ())
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn manual_test_json_is_checked() {
+        let grid: Grid = "S.#\n#.E\n".parse().unwrap();
+        let json = serde_json::to_string(&grid).unwrap();
+        let read: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(grid.spaces, read.spaces);
+
+        let short = json.replace(r#","height":2"#, r#","height":3"#);
+        let err = serde_json::from_str::<Grid>(&short).unwrap_err();
+        assert!(err.to_string().contains("a 3x3 maze can't have 6 spaces"));
+
+        let moved = json.replace(r#"{"x":2,"y":1}"#, r#"{"x":0,"y":0}"#);
+        assert!(serde_json::from_str::<Grid>(&moved).is_err());
+
+        let no_end = json.replace(r#"{"End":{"x":2,"y":1}}"#, r#""Empty""#);
+        assert!(serde_json::from_str::<Grid>(&no_end).is_err());
+    }
+
     // Found Cant have a grid size of width * height > usize::max
     #[test]
     fn fuzzy_test_get() {
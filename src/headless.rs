@@ -0,0 +1,241 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::grid::Grid;
+use crate::maze::{GeneratorKind, Progress, SolverKind};
+use crate::point::Point;
+use crate::race::{Race, Standings};
+use crate::run::RunResult;
+
+/// Generates and solves mazes without touching the terminal: no raw mode, no
+/// alternate screen and no `terminal::size()`, so it works in pipes and CI.
+pub struct Headless {
+    pub generator: GeneratorKind,
+    pub solver: SolverKind,
+    /// Race these solvers against each other instead of running `solver`.
+    pub race: Vec<SolverKind>,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// How [`Headless::write`] writes each result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The solved grid and what was found, for people.
+    #[default]
+    Text,
+    /// A line of JSON per solver.
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+impl Headless {
+    /// Builds the maze for `seed` and solves it at full speed. Returns the
+    /// solved grid alongside the run, or `None` if the grid is too small to hold
+    /// a start and an end.
+    pub fn run(&self, seed: u64) -> Option<(Grid, RunResult)> {
+        let maze = self
+            .generator
+            .build(Grid::new(self.width, self.height), seed)?;
+        let solution = self.solver.solve(
+            maze.start,
+            maze.grid.clone(),
+            Duration::ZERO,
+            &Progress::default(),
+        );
+        let grid = maze.grid.lock().unwrap().clone();
+        let result = RunResult::new(self.generator.name(), seed, self.solver.name(), solution);
+        Some((grid, result))
+    }
+
+    /// Runs, or races, the maze for `seed` and writes the result to `out` in
+    /// `format`.
+    pub fn write(&self, out: &mut impl Write, seed: u64, format: OutputFormat) -> io::Result<()> {
+        if !self.race.is_empty() {
+            return self.write_race(out, seed, format);
+        }
+        let (grid, result) = self.run(seed).ok_or_else(too_small)?;
+        match format {
+            OutputFormat::Text => {}
+            #[cfg(feature = "serde")]
+            OutputFormat::Json => return writeln!(out, "{}", result.to_json()?),
+        }
+
+        write!(out, "{grid}")?;
+        writeln!(out, "generator: {}", result.generator)?;
+        writeln!(out, "solver: {}", result.solver)?;
+        writeln!(out, "seed: {}", result.seed)?;
+        write_solution(out, &result.visited, &result.path)?;
+        writeln!(out)
+    }
+
+    /// Races [`Headless::race`] on the maze for `seed` at full speed and
+    /// writes each lane, then who won.
+    fn write_race(&self, out: &mut impl Write, seed: u64, format: OutputFormat) -> io::Result<()> {
+        let maze = self
+            .generator
+            .build(Grid::new(self.width, self.height), seed)
+            .ok_or_else(too_small)?;
+        let race = Race::new(&maze.grid.lock().unwrap(), &self.race);
+        match format {
+            // The text only needs the counts the race keeps.
+            OutputFormat::Text => {
+                race.run(maze.start);
+            }
+            #[cfg(feature = "serde")]
+            OutputFormat::Json => {
+                let solutions = race.run(maze.start);
+                for (lane, solution) in race.lanes.iter().zip(solutions) {
+                    let name = self.generator.name();
+                    let result = RunResult::new(name, seed, lane.solver.name(), solution);
+                    writeln!(out, "{}", result.to_json()?)?;
+                }
+                return Ok(());
+            }
+        }
+        let standings = Standings::from_stats(&race.stats());
+
+        writeln!(out, "generator: {}", self.generator.name())?;
+        writeln!(out, "seed: {seed}")?;
+        // The same counts the standings are decided on.
+        for (lane, stats) in race.lanes.iter().zip(race.stats()) {
+            let path = match stats.path_len {
+                0 => "none".to_string(),
+                len => len.to_string(),
+            };
+            writeln!(
+                out,
+                "{}: expanded {}  path {}",
+                lane.solver.name(),
+                stats.expanded,
+                path
+            )?;
+        }
+        writeln!(out, "{}", race.describe(&standings))?;
+        writeln!(out)
+    }
+}
+
+fn too_small() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "grid is too small for a maze")
+}
+
+/// Writes how many spaces were visited and the path as `x,y` pairs.
+pub fn write_solution(out: &mut impl Write, visited: &[Point], path: &[Point]) -> io::Result<()> {
+    writeln!(out, "visited: {}", visited.len())?;
+    if !path.is_empty() {
+        let path: Vec<String> = path
+            .iter()
+            .map(|point| format!("{},{}", point.x, point.y))
+            .collect();
+        writeln!(out, "path: {}", path.join(" "))
+    } else {
+        writeln!(out, "path: none")
+    }
+}
+
+/// Solves the maze in `file` with `solver` and writes the solved grid and
+/// the path to `out`. Fails once that is written if there is no path.
+pub fn solve_file(out: &mut impl Write, file: &Path, solver: SolverKind) -> io::Result<()> {
+    let grid: Grid = std::fs::read_to_string(file)?
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let start = grid
+        .start()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "maze has no start"))?;
+
+    let grid = Arc::new(Mutex::new(grid));
+    let solution = solver.solve(start, grid.clone(), Duration::ZERO, &Progress::default());
+
+    write!(out, "{}", grid.lock().unwrap())?;
+    write_solution(out, &solution.visited, &solution.path)?;
+    if solution.path.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "maze has no solution",
+        ));
+    }
+    Ok(())
+}
+
+/// Builds a `width` by `height` maze with `generator` from `seed` and writes
+/// it to `file` in the plain-text maze format.
+pub fn generate_file(
+    file: &Path,
+    generator: GeneratorKind,
+    width: usize,
+    height: usize,
+    seed: u64,
+) -> io::Result<()> {
+    let generated = generator
+        .build(Grid::new(width, height), seed)
+        .ok_or_else(too_small)?;
+    let grid = generated.grid.lock().unwrap();
+    std::fs::write(file, grid.to_string())
+}
+
+#[cfg(test)]
+mod headless_tests {
+    use super::{Headless, OutputFormat};
+    use crate::maze::{GeneratorKind, SolverKind};
+    use crate::space::Space;
+
+    #[test]
+    fn manual_test_headless_run() {
+        let headless = Headless {
+            generator: GeneratorKind::Random,
+            solver: SolverKind::Bfs,
+            race: vec![],
+            width: 30,
+            height: 10,
+        };
+
+        let (grid, result) = headless.run(7).unwrap();
+
+        assert_eq!((30, 10), (grid.width, grid.height));
+        assert_eq!(7, result.seed);
+        assert_eq!("random", result.generator);
+        assert_eq!("bfs", result.solver);
+        for point in result.path.iter().skip(1).rev().skip(1) {
+            assert_eq!(Some(Space::Path), grid.get(*point));
+        }
+        assert_eq!(headless.run(7).unwrap().1, result);
+    }
+
+    #[test]
+    fn manual_test_headless_too_small() {
+        let headless = Headless {
+            generator: GeneratorKind::Random,
+            solver: SolverKind::Bfs,
+            race: vec![],
+            width: 1,
+            height: 1,
+        };
+
+        assert!(headless.run(7).is_none());
+        assert!(headless
+            .write(&mut Vec::new(), 7, OutputFormat::Text)
+            .is_err());
+    }
+
+    #[test]
+    fn manual_test_headless_race() {
+        let headless = Headless {
+            generator: GeneratorKind::Random,
+            solver: SolverKind::Bfs,
+            race: vec![SolverKind::Bfs, SolverKind::AStar],
+            width: 20,
+            height: 10,
+        };
+
+        let mut out = Vec::new();
+        headless.write(&mut out, 3, OutputFormat::Text).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(vec!["generator: random", "seed: 3"], lines[..2]);
+        assert!(lines[2].starts_with("bfs: expanded "));
+        assert!(lines[3].starts_with("a-star: expanded "));
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crossterm::style::{Color, Colors};
+
+use crate::buffer::{Buffer, Cell, Heatmap};
+use crate::maze::Stats;
+
+/// The status lines drawn under the grid while a maze is solved: what is
+/// running, live solver counters and the key hints.
+#[derive(Debug, Clone)]
+pub struct Hud {
+    pub generator: &'static str,
+    pub solver: &'static str,
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    /// A message for the user, such as why the seed couldn't be copied,
+    /// shown after the metrics.
+    pub notice: Option<String>,
+}
+
+impl Hud {
+    /// Terminal rows the HUD takes up.
+    pub const ROWS: usize = 2;
+
+    const BAR: Colors = Colors {
+        foreground: Some(Color::Black),
+        background: Some(Color::Grey),
+    };
+
+    /// The first line: the maze, and how far the solver has got after
+    /// `elapsed`.
+    pub fn metrics(&self, stats: Stats, elapsed: Duration) -> String {
+        let path = match stats.path_len {
+            0 => "-".to_string(),
+            len => len.to_string(),
+        };
+        let paused = if stats.paused { "  paused" } else { "" };
+        format!(
+            " {} / {}  {}x{}  expanded {}  frontier {}  path {}  {:.2}s{}",
+            self.generator,
+            self.solver,
+            self.width,
+            self.height,
+            stats.expanded,
+            stats.frontier,
+            path,
+            elapsed.as_secs_f64(),
+            paused,
+        )
+    }
+
+    /// The second line: the seed and key hints.
+    pub fn hints(&self) -> String {
+        format!(
+            " seed {}  [c] copy seed  [t] theme  [r] regenerate  [n] next maze  [q] quit  [arrows] pan  [+/-] zoom  [f] follow  [m] minimap  [[/]] step  [home/end] replay  [space] pause  [a] arrows",
+            self.seed
+        )
+    }
+
+    /// Draws the HUD over rows `y` and `y + 1` of `buf`. While an earlier
+    /// step is replayed, `replay` holds the step shown and the number of
+    /// steps. With a heatmap, its legend up to the given distance follows the
+    /// key hints.
+    pub fn draw(
+        &self,
+        buf: &mut Buffer,
+        y: usize,
+        stats: Stats,
+        elapsed: Duration,
+        replay: Option<(usize, usize)>,
+        legend: Option<(Heatmap, u32)>,
+    ) {
+        buf.clear_row(y, Self::BAR);
+        let x = buf.put_str(0, y, &self.metrics(stats, elapsed), Self::BAR);
+        let x = match replay {
+            Some((step, steps)) => {
+                buf.put_str(x, y, &format!("  replay {step}/{steps}"), Self::BAR)
+            }
+            None => x,
+        };
+        if let Some(notice) = &self.notice {
+            buf.put_str(x, y, &format!("  {notice}"), Self::BAR);
+        }
+
+        let colors = Cell::default().colors;
+        buf.clear_row(y + 1, colors);
+        let x = buf.put_str(0, y + 1, &self.hints(), colors);
+        if let Some((heatmap, max)) = legend {
+            let x = buf.put_str(x, y + 1, "  distance ", colors);
+            heatmap.draw_legend(buf, x, y + 1, max, 16);
+        }
+    }
+
+    /// The bottom line in race mode: the seed, the result once every solver
+    /// is done, and key hints.
+    pub fn draw_race(&self, buf: &mut Buffer, y: usize, result: &str) {
+        let colors = Cell::default().colors;
+        buf.clear_row(y, colors);
+        let mut text = format!(
+            " seed {}  {}x{}  {}  [c] copy seed  [t] theme  [r] regenerate  [n] next maze  [q] quit",
+            self.seed, self.width, self.height, result
+        );
+        if let Some(notice) = &self.notice {
+            text.push_str(&format!("  {notice}"));
+        }
+        buf.put_str(0, y, &text, colors);
+    }
+}
+
+/// Draws the two status rows under one lane of a race at `y`: the solver's
+/// name and what it has won, then its counters.
+pub fn draw_lane(
+    buf: &mut Buffer,
+    y: usize,
+    solver: &str,
+    stats: Stats,
+    elapsed: Duration,
+    won: &[&str],
+) {
+    buf.clear_row(y, Hud::BAR);
+    let mut title = format!(" {solver}");
+    if !won.is_empty() {
+        title.push_str(&format!("  * {}", won.join(", ")));
+    }
+    buf.put_str(0, y, &title, Hud::BAR);
+
+    let colors = Cell::default().colors;
+    buf.clear_row(y + 1, colors);
+    let path = match stats.path_len {
+        0 => "-".to_string(),
+        len => len.to_string(),
+    };
+    let text = format!(
+        " expanded {}  frontier {}  path {}  {:.2}s",
+        stats.expanded,
+        stats.frontier,
+        path,
+        elapsed.as_secs_f64()
+    );
+    buf.put_str(0, y + 1, &text, colors);
+}
+
+/// How fast frames are really drawn, over the last second.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    /// When each recent frame was drawn, how long it took and the bytes it
+    /// wrote, oldest first.
+    frames: VecDeque<(Instant, Duration, usize)>,
+}
+
+impl FrameStats {
+    /// Width the reading's bar is padded to, so it keeps the same length.
+    const WIDTH: usize = 32;
+
+    /// Notes a frame drawn at `at` that took `took` to compose and wrote
+    /// `bytes`, forgetting frames more than a second older.
+    pub fn record(&mut self, at: Instant, took: Duration, bytes: usize) {
+        while let Some(&(first, _, _)) = self.frames.front() {
+            if at.duration_since(first) < Duration::from_secs(1) {
+                break;
+            }
+            self.frames.pop_front();
+        }
+        self.frames.push_back((at, took, bytes));
+    }
+
+    /// Frames in the last second, the mean frame time and the mean bytes
+    /// per frame, e.g. ` 60 fps  0.42 ms  152 B/frame`.
+    pub fn text(&self) -> String {
+        let count = self.frames.len().max(1);
+        let took: Duration = self.frames.iter().map(|&(_, took, _)| took).sum();
+        let bytes: usize = self.frames.iter().map(|&(_, _, bytes)| bytes).sum();
+        format!(
+            " {} fps  {:.2} ms  {} B/frame",
+            self.frames.len(),
+            took.as_secs_f64() * 1000.0 / count as f64,
+            bytes / count
+        )
+    }
+
+    /// Draws the reading over row `y` of `buf`, a row of its own under the
+    /// HUD so it doesn't hide any of the grid.
+    pub fn draw(&self, buf: &mut Buffer, y: usize) {
+        buf.clear_row(y, Cell::default().colors);
+        let text = format!("{:<width$}", self.text(), width = Self::WIDTH);
+        buf.put_str(0, y, &text, Hud::BAR);
+    }
+}
+
+#[cfg(test)]
+mod hud_tests {
+    use std::time::{Duration, Instant};
+
+    use super::{FrameStats, Hud};
+    use crate::{
+        buffer::Buffer, grid::Grid, maze::Stats, point::Point, space::Space, viewport::Viewport,
+    };
+
+    #[test]
+    fn manual_test_draw_under_grid() {
+        let hud = Hud {
+            generator: "random",
+            solver: "bfs",
+            seed: 7,
+            width: 3,
+            height: 1,
+            notice: None,
+        };
+        let stats = Stats {
+            expanded: 12,
+            frontier: 3,
+            path_len: 0,
+            current: None,
+            paused: false,
+        };
+        let mut grid = Grid::new(3, 1);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Obstacle;
+
+        let mut buf = Buffer::new(80, 1 + Hud::ROWS);
+        buf.set_viewport(Viewport::centred(80, 1, 80, 1));
+        hud.draw(&mut buf, 1, stats, Duration::from_millis(1500), None, None);
+        buf.flush_diff(&mut Vec::new(), &grid).unwrap();
+
+        let row = |y: usize| -> String {
+            buf.cells[y * 80..(y + 1) * 80]
+                .iter()
+                .map(|cell| cell.ch)
+                .collect()
+        };
+        assert_eq!(
+            " random / bfs  3x1  expanded 12  frontier 3  path -  1.50s",
+            row(1).trim_end()
+        );
+        assert!(row(2).starts_with(" seed 7  [c] copy seed"));
+
+        let paused = Stats {
+            paused: true,
+            ..stats
+        };
+        hud.draw(&mut buf, 1, paused, Duration::ZERO, Some((40, 90)), None);
+        let metrics: String = buf.cells[80..160].iter().map(|cell| cell.ch).collect();
+        assert!(metrics.trim_end().ends_with("0.00s  paused  replay 40/90"));
+        let hud = Hud {
+            notice: Some("copy failed".to_string()),
+            ..hud
+        };
+        hud.draw(&mut buf, 1, paused, Duration::ZERO, None, None);
+        let metrics: String = buf.cells[80..160].iter().map(|cell| cell.ch).collect();
+        assert!(metrics.trim_end().ends_with("paused  copy failed"));
+        // The grid fills the viewport above, blank where it doesn't reach.
+        assert_eq!(Buffer::new(1, 1).paint(Space::Obstacle), buf.cells[0]);
+        assert_eq!(' ', buf.cells[3].ch);
+    }
+
+    #[test]
+    fn manual_test_frame_stats() {
+        let start = Instant::now();
+        let mut stats = FrameStats::default();
+        assert_eq!(" 0 fps  0.00 ms  0 B/frame", stats.text());
+
+        for i in 0..30 {
+            let at = start + Duration::from_millis(50 * i);
+            stats.record(at, Duration::from_millis(2), 100 + i as usize);
+        }
+        // Only the 20 frames from the last second count.
+        assert_eq!(" 20 fps  2.00 ms  119 B/frame", stats.text());
+    }
+}
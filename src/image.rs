@@ -0,0 +1,484 @@
+use std::io::{self, Write};
+#[cfg(feature = "gif")]
+use std::time::Duration;
+
+use crate::grid::Grid;
+#[cfg(feature = "gif")]
+use crate::maze::Change;
+use crate::point::Point;
+use crate::space::Space;
+
+/// A picture of a grid with each space drawn as a square of pixels, in the
+/// colours the default theme gives it on the terminal. Spaces shown there as
+/// a glyph are drawn as a smaller square of the glyph's colour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// RGB, row by row.
+    pixels: Vec<[u8; 3]>,
+}
+
+/// The colour of spaces the terminal leaves in its own background colour.
+const BACKGROUND: [u8; 3] = [0, 0, 0];
+
+/// How each kind of space is drawn, by [`kind`]: a fill, and a colour for a
+/// smaller square in the middle. Spaces the terminal shows as a glyph on its
+/// own background are filled with that and have the glyph's colour in the
+/// middle, so they stand apart from spaces drawn as a solid block. These are
+/// the default theme's colours as xterm shows them, kept here rather than
+/// read from the theme so images don't need the terminal renderer.
+const SWATCHES: [([u8; 3], [u8; 3]); 7] = [
+    // Obstacle, dark red.
+    ([205, 0, 0], [205, 0, 0]),
+    (BACKGROUND, BACKGROUND),
+    // Frontier, a yellow glyph.
+    (BACKGROUND, [255, 255, 0]),
+    // Visited, a white glyph.
+    (BACKGROUND, [255, 255, 255]),
+    // Path, palette grey.
+    ([229, 229, 229], [229, 229, 229]),
+    // Start, dark blue.
+    ([0, 0, 238], [0, 0, 238]),
+    // End, dark green.
+    ([0, 205, 0], [0, 205, 0]),
+];
+
+/// Where `space` is in [`SWATCHES`].
+fn kind(space: Space) -> usize {
+    match space {
+        Space::Obstacle => 0,
+        Space::Empty => 1,
+        Space::Frontier(_) => 2,
+        Space::Visited(_) => 3,
+        Space::Path => 4,
+        Space::Start(_) => 5,
+        Space::End(_) => 6,
+    }
+}
+
+/// The kind of space pixel `x`, `y` shows when `grid` is drawn with `scale`
+/// by `scale` pixels per space, and whether it is in the space's middle
+/// square. The middle leaves a quarter of the space on each side, so it takes
+/// up all of spaces too small to leave any.
+fn locate(grid: &Grid, scale: usize, x: usize, y: usize) -> (usize, bool) {
+    let space = grid.spaces[Point::new(x / scale, y / scale).index(grid.width)];
+    let margin = scale / 4;
+    let middle = |offset| (margin..scale - margin).contains(&offset);
+    (kind(space), middle(x % scale) && middle(y % scale))
+}
+
+/// The colour of pixel `x`, `y` as [`locate`] finds it.
+fn rgb(grid: &Grid, scale: usize, x: usize, y: usize) -> [u8; 3] {
+    let (kind, middle) = locate(grid, scale, x, y);
+    let (fill, inner) = SWATCHES[kind];
+    match middle {
+        true => inner,
+        false => fill,
+    }
+}
+
+impl Image {
+    /// Draws `grid` with `scale` by `scale` pixels per space. Fails if the
+    /// image would be too big to hold in memory.
+    pub fn from_grid(grid: &Grid, scale: usize) -> io::Result<Self> {
+        let scale = scale.max(1);
+        let too_big = || io::Error::new(io::ErrorKind::InvalidInput, "image is too big");
+        let width = grid.width.checked_mul(scale).ok_or_else(too_big)?;
+        let height = grid.height.checked_mul(scale).ok_or_else(too_big)?;
+        let len = width.checked_mul(height).ok_or_else(too_big)?;
+        let mut pixels = Vec::new();
+        pixels.try_reserve_exact(len).map_err(|_| too_big())?;
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(rgb(grid, scale, x, y));
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Writes the image as a binary PPM (P6).
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(self.pixels.as_flattened())?;
+        out.flush()
+    }
+
+    /// Writes the image as a PNG.
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, out: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(self.pixels.as_flattened())
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+}
+
+/// An animated GIF of a solve, written a frame at a time.
+#[cfg(feature = "gif")]
+pub struct Animation<W: Write> {
+    encoder: gif::Encoder<W>,
+    scale: usize,
+    /// Where each kind of space's fill and middle colours are in the palette,
+    /// by [`kind`]. The palette holds just the colours in [`SWATCHES`], so
+    /// pixels map to it exactly with no quantising.
+    indices: [[u8; 2]; 7],
+}
+
+#[cfg(feature = "gif")]
+impl<W: Write> Animation<W> {
+    /// How long the finished solve stays up before the animation loops.
+    pub const HOLD: Duration = Duration::from_secs(2);
+
+    /// The most frames [`Animation::replay`] writes, the first and last
+    /// included, so a big grid's GIF stays a sensible size.
+    pub const MAX_FRAMES: usize = 300;
+
+    /// Starts a looping GIF of a `width` by `height` grid with `scale` by
+    /// `scale` pixels per space.
+    pub fn new(out: W, width: usize, height: usize, scale: usize) -> io::Result<Self> {
+        let scale = scale.max(1);
+        let too_big = || io::Error::new(io::ErrorKind::InvalidInput, "grid is too big for a GIF");
+        let pixels_wide = u16::try_from(width * scale).map_err(|_| too_big())?;
+        let pixels_high = u16::try_from(height * scale).map_err(|_| too_big())?;
+
+        let mut palette = vec![BACKGROUND];
+        let mut index = |color| {
+            if !palette.contains(&color) {
+                palette.push(color);
+            }
+            palette.iter().position(|&entry| entry == color).unwrap() as u8
+        };
+        let indices = SWATCHES.map(|(fill, inner)| [index(fill), index(inner)]);
+        let mut encoder = gif::Encoder::new(out, pixels_wide, pixels_high, &palette.concat())
+            .map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        Ok(Self {
+            encoder,
+            scale,
+            indices,
+        })
+    }
+
+    /// Adds a frame of `grid` shown for `delay`, rounded to the GIF's
+    /// hundredths of a second and at least one of them.
+    pub fn frame(&mut self, grid: &Grid, delay: Duration) -> io::Result<()> {
+        let corner = Point::new(grid.width.saturating_sub(1), grid.height.saturating_sub(1));
+        self.patch(grid, (Point::new(0, 0), corner), delay)
+    }
+
+    /// Adds a frame that redraws just the spaces of `grid` from `area.0` to
+    /// `area.1` inclusive, leaving the rest of the picture as the frames
+    /// before left it.
+    fn patch(&mut self, grid: &Grid, area: (Point, Point), delay: Duration) -> io::Result<()> {
+        let (from, to) = area;
+        let (left, top) = (from.x * self.scale, from.y * self.scale);
+        let (right, bottom) = ((to.x + 1) * self.scale, (to.y + 1) * self.scale);
+        let mut buffer = Vec::with_capacity((right - left) * (bottom - top));
+        for y in top..bottom {
+            for x in left..right {
+                let (kind, middle) = locate(grid, self.scale, x, y);
+                buffer.push(self.indices[kind][middle as usize]);
+            }
+        }
+        let frame = gif::Frame {
+            left: left as u16,
+            top: top as u16,
+            width: (right - left) as u16,
+            height: (bottom - top) as u16,
+            delay: (delay.as_millis() / 10).clamp(1, u16::MAX as u128) as u16,
+            dispose: gif::DisposalMethod::Keep,
+            buffer: buffer.into(),
+            ..gif::Frame::default()
+        };
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+
+    /// Animates a solve: `grid` as it was before the solver started, then a
+    /// frame every `every` steps in the solver's `changes`, each shown for
+    /// `delay`. A step is one space expanded, with the spaces it discovers,
+    /// or one space of the path revealed once the end is found. Solves too
+    /// long to fit in [`Animation::MAX_FRAMES`] at `every` steps a frame take
+    /// more steps a frame instead. The finished grid is held for
+    /// [`Animation::HOLD`].
+    pub fn replay(
+        &mut self,
+        mut grid: Grid,
+        changes: impl IntoIterator<Item = Change>,
+        every: usize,
+        delay: Duration,
+    ) -> io::Result<()> {
+        let changes: Vec<Change> = changes.into_iter().collect();
+        let steps = changes
+            .iter()
+            .filter(|change| starts_step(change.space))
+            .count();
+        // The first and last frames come on top of one every `every` steps.
+        let every = every
+            .max(1)
+            .max(steps.div_ceil(Self::MAX_FRAMES.saturating_sub(1)));
+        self.frame(&grid, delay)?;
+        let mut since_frame = 0;
+        // The corners of the spaces changed since the last frame, which is
+        // all the next one has to redraw.
+        let mut changed: Option<(Point, Point)> = None;
+        for Change { point, space, .. } in changes {
+            // A step starts with its change, so the frame for the steps
+            // before it goes first.
+            if starts_step(space) {
+                if since_frame == every {
+                    if let Some(area) = changed.take() {
+                        self.patch(&grid, area, delay)?;
+                    }
+                    since_frame = 0;
+                }
+                since_frame += 1;
+            }
+            if let Some(slot) = grid.get_mut(point) {
+                *slot = space;
+                changed = Some(match changed {
+                    Some((from, to)) => (
+                        Point::new(from.x.min(point.x), from.y.min(point.y)),
+                        Point::new(to.x.max(point.x), to.y.max(point.y)),
+                    ),
+                    None => (point, point),
+                });
+            }
+        }
+        self.frame(&grid, Self::HOLD)
+    }
+}
+
+/// Whether `space` being set starts a step of a solve: a space expanded, or
+/// a space put on the path.
+#[cfg(feature = "gif")]
+fn starts_step(space: Space) -> bool {
+    matches!(space, Space::Visited(_) | Space::Path)
+}
+
+#[cfg(test)]
+mod image_tests {
+    use super::Image;
+    use crate::{grid::Grid, point::Point, space::Space};
+
+    fn grid() -> Grid {
+        // # .
+        // . *
+        let mut grid = Grid::new(2, 2);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(1, 1)).unwrap() = Space::Path;
+        grid
+    }
+
+    #[test]
+    fn manual_test_from_grid() {
+        let image = Image::from_grid(&grid(), 3).unwrap();
+        assert_eq!((6, 6), (image.width, image.height));
+        // Obstacles are dark red and the path is palette grey, as on screen.
+        assert_eq!(Some([205, 0, 0]), image.pixel(2, 2));
+        assert_eq!(Some([0, 0, 0]), image.pixel(3, 2));
+        assert_eq!(Some([229, 229, 229]), image.pixel(5, 5));
+        assert_eq!(None, image.pixel(6, 0));
+        assert!(Image::from_grid(&grid(), usize::MAX).is_err());
+    }
+
+    #[test]
+    fn manual_test_glyphs_stand_apart_from_blocks() {
+        // Visited spaces show as a glyph on screen and the path as a block,
+        // in colours too close to tell apart if both were filled.
+        let mut grid = Grid::new(2, 1);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Visited(1);
+        *grid.get_mut(Point::new(1, 0)).unwrap() = Space::Path;
+        let image = Image::from_grid(&grid, 4).unwrap();
+
+        let visited: Vec<_> = (0..4).map(|x| image.pixel(x, 1).unwrap()).collect();
+        let path: Vec<_> = (4..8).map(|x| image.pixel(x, 1).unwrap()).collect();
+        assert_eq!(vec![[0; 3], [255; 3], [255; 3], [0; 3]], visited);
+        assert_eq!(vec![[229; 3]; 4], path);
+        assert!(visited.iter().all(|pixel| !path.contains(pixel)));
+    }
+
+    #[test]
+    fn manual_test_write_ppm() {
+        let mut out = Vec::new();
+        Image::from_grid(&grid(), 1)
+            .unwrap()
+            .write_ppm(&mut out)
+            .unwrap();
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(header.as_slice(), &out[..header.len()]);
+        assert_eq!(header.len() + 2 * 2 * 3, out.len());
+        assert_eq!([205, 0, 0], out[header.len()..header.len() + 3]);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn manual_test_colors_match_the_default_theme() {
+        use crossterm::style::Color;
+
+        use super::{kind, BACKGROUND, SWATCHES};
+        use crate::{buffer::Cell, color_depth};
+
+        let rgb = |color: Option<Color>| {
+            color
+                .and_then(color_depth::to_rgb)
+                .map_or(BACKGROUND, |(r, g, b)| [r, g, b])
+        };
+        let origin = Point::new(0, 0);
+        let spaces = [
+            Space::Obstacle,
+            Space::Empty,
+            Space::Frontier(0),
+            Space::Visited(0),
+            Space::Path,
+            Space::Start(origin),
+            Space::End(origin),
+        ];
+        for space in spaces {
+            let cell = Cell::from(space);
+            let on_screen = (rgb(cell.colors.background), rgb(cell.block_color()));
+            assert_eq!(on_screen, SWATCHES[kind(space)], "{space:?}");
+        }
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn manual_test_write_png() {
+        let mut out = Vec::new();
+        Image::from_grid(&grid(), 4)
+            .unwrap()
+            .write_png(&mut out)
+            .unwrap();
+        assert_eq!(b"\x89PNG\r\n\x1a\n".as_slice(), &out[..8]);
+
+        let decoder = png::Decoder::new(out.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((8, 8), (reader.info().width, reader.info().height));
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn manual_test_animation() {
+        use std::time::Duration;
+
+        use super::Animation;
+        use crate::maze::Change;
+
+        let before = grid();
+        let changes = [Point::new(1, 0), Point::new(0, 1), Point::new(1, 1)].map(|point| Change {
+            point,
+            space: Space::Visited(1),
+            from: None,
+        });
+
+        let mut out = Vec::new();
+        let mut animation = Animation::new(&mut out, 2, 2, 2).unwrap();
+        animation
+            .replay(before, changes, 2, Duration::from_millis(50))
+            .unwrap();
+        drop(animation);
+        assert_eq!(b"GIF89a".as_slice(), &out[..6]);
+
+        let mut decoder = gif::DecodeOptions::new();
+        decoder.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = decoder.read_info(out.as_slice()).unwrap();
+        let mut delays = vec![];
+        let mut last = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((4, 4), (frame.width, frame.height));
+            delays.push(frame.delay);
+            last = frame.buffer.to_vec();
+        }
+        // The start, one frame for every two steps, and the held end.
+        assert_eq!(vec![5, 5, 200], delays);
+        // Only the obstacle is left out of the search.
+        assert_eq!(
+            2,
+            last.iter().collect::<std::collections::HashSet<_>>().len()
+        );
+    }
+
+    #[cfg(feature = "gif")]
+    fn frame_count(out: &[u8]) -> usize {
+        let mut decoder = gif::DecodeOptions::new().read_info(out).unwrap();
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        frames
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn manual_test_animation_reveals_the_path() {
+        use std::time::Duration;
+
+        use super::Animation;
+        use crate::maze::Change;
+
+        let change = |x, space| Change {
+            point: Point::new(x, 0),
+            space,
+            from: None,
+        };
+        let mut changes = vec![change(0, Space::Visited(0))];
+        changes.extend((0..4).map(|x| change(x, Space::Path)));
+
+        let mut out = Vec::new();
+        let mut animation = Animation::new(&mut out, 4, 1, 1).unwrap();
+        animation
+            .replay(Grid::new(4, 1), changes, 1, Duration::from_millis(10))
+            .unwrap();
+        drop(animation);
+        // The start, a frame after each of the five steps bar the last, and
+        // the held end.
+        assert_eq!(6, frame_count(&out));
+
+        // Frames between the first and last redraw only what changed.
+        let mut decoder = gif::DecodeOptions::new().read_info(out.as_slice()).unwrap();
+        let mut areas = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            areas.push((frame.left, frame.width));
+        }
+        assert_eq!(vec![(0, 4), (0, 1), (0, 1), (1, 1), (2, 1), (0, 4)], areas);
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn manual_test_animation_frame_cap() {
+        use std::time::Duration;
+
+        use super::Animation;
+        use crate::maze::Change;
+
+        let changes = (0..10_000).map(|i| Change {
+            point: Point::new(0, 0),
+            space: Space::Visited(i),
+            from: None,
+        });
+
+        let mut out = Vec::new();
+        let mut animation = Animation::new(&mut out, 1, 1, 1).unwrap();
+        animation
+            .replay(Grid::new(1, 1), changes, 1, Duration::from_millis(10))
+            .unwrap();
+        drop(animation);
+        // Steps are spread over as many frames as fit, give or take rounding.
+        let max = Animation::<Vec<u8>>::MAX_FRAMES;
+        assert!((max - 10..=max).contains(&frame_count(&out)));
+    }
+}
@@ -0,0 +1,35 @@
+//! Grid mazes, the generators that build them and the solvers that search them.
+//!
+//! The terminal renderer lives behind the `tui` feature so the core types can be
+//! used without pulling in crossterm.
+
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod bench;
+#[cfg(feature = "tui")]
+pub mod buffer;
+pub mod cast;
+#[cfg(feature = "tui")]
+pub mod color_depth;
+pub mod export;
+pub mod grid;
+pub mod headless;
+#[cfg(feature = "tui")]
+pub mod hud;
+pub mod image;
+pub mod maze;
+#[cfg(feature = "tui")]
+pub mod minimap;
+pub mod point;
+pub mod race;
+pub mod run;
+#[cfg(feature = "tui")]
+pub mod screen_state;
+pub mod space;
+pub mod svg;
+#[cfg(feature = "tui")]
+pub mod theme;
+pub mod timeline;
+#[cfg(feature = "tui")]
+pub mod viewport;
+pub mod wall_maze;
@@ -1,75 +1,182 @@
-pub mod buffer;
-pub mod grid;
-pub mod maze;
-pub mod point;
-pub mod screen_state;
-pub mod space;
+mod cli;
 
-use crossterm::event::{poll, read, Event, KeyCode};
+use clap::{CommandFactory, Parser};
 use crossterm::terminal;
-use maze::{bfs, RandomMaze};
+use rand::Rng;
 
-use std::sync::Mutex;
-use std::thread::{self, JoinHandle};
+use std::io::{self, stdout, IsTerminal};
+use std::process::ExitCode;
 use std::time::Duration;
-use std::{io::stdout, sync::Arc};
 
-use buffer::Buffer;
-use screen_state::ScreenState;
+use cli::{Cli, Command, MazeArgs, RunArgs};
+use pathrs::app::App;
+use pathrs::bench::Bench;
+use pathrs::export::{self, Export};
+use pathrs::headless::{self, Headless, OutputFormat};
+use pathrs::wall_maze::WallMaze;
 
-fn main() -> std::io::Result<()> {
-    let _screen_state = ScreenState::enable()?;
-
-    let default_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |info| {
-        ScreenState::disable();
-        default_hook(info);
-    }));
-
-    loop {
-        let (width, height) = terminal::size()?;
-        let grid = grid::Grid::new(width as usize, height as usize);
-
-        let grid = Arc::new(Mutex::new(grid));
-
-        let mut maze = maze::RandomMaze::new(grid);
-        if maze.build_maze().is_none() {
-            continue;
+fn main() -> ExitCode {
+    match run_command(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
         }
-        let start = maze.start;
-
-        let thread_grid = maze.grid.clone();
-        let maze_thread = thread::spawn(move || {
-            bfs(start, thread_grid);
-        });
-
-        let writer_thread = thread::spawn(move || {
-            let _ = writer_thread(maze_thread, maze);
-        });
+    }
+}
 
-        if poll(Duration::from_millis(15))? {
-            if let Event::Key(event) = read()? {
-                if event.code == KeyCode::Char('q') {
-                    break;
-                }
+fn run_command(cli: Cli) -> io::Result<()> {
+    let command = cli.command.unwrap_or(Command::Run(cli.run));
+    if let Command::Run(args) = &command {
+        // A race of one is just a run.
+        if args.race.len() == 1 {
+            Cli::command()
+                .error(
+                    clap::error::ErrorKind::TooFewValues,
+                    "--race takes two or more solvers",
+                )
+                .exit();
+        }
+    }
+    match command {
+        Command::Run(args) if args.headless || !stdout().is_terminal() => run_headless(args),
+        Command::Run(args) => run(args),
+        Command::Solve { file, solver } => headless::solve_file(&mut stdout(), &file, solver),
+        Command::Generate { file, maze } => {
+            let (width, height) = grid_size(&maze)?;
+            let seed = seed(&maze);
+            headless::generate_file(&file, maze.generator, width, height, seed)?;
+            println!("seed: {seed}");
+            Ok(())
+        }
+        Command::Export {
+            file,
+            maze,
+            solver,
+            scale,
+            every,
+            delay,
+            shade_visited,
+        } => {
+            let (width, height) = grid_size(&maze)?;
+            let export = Export {
+                generator: maze.generator,
+                solver,
+                width,
+                height,
+                scale,
+                every,
+                delay: Duration::from_millis(delay),
+                shade_visited,
+            };
+            let seed = seed(&maze);
+            export.save(&file, seed)?;
+            println!("seed: {seed}");
+            Ok(())
+        }
+        Command::Walls {
+            width,
+            height,
+            seed,
+            svg,
+            shade_visited,
+        } => {
+            let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+            let mut maze = WallMaze::new(width, height);
+            maze.carve(seed);
+            print!("{maze}");
+            if let Some(file) = svg {
+                export::save_walls(&file, &maze, shade_visited)?;
             }
+            println!("seed: {seed}");
+            Ok(())
+        }
+        Command::Bench {
+            count,
+            maze,
+            solver,
+            frames,
+        } => {
+            let (width, height) = grid_size(&maze)?;
+            let bench = Bench {
+                generator: maze.generator,
+                solver,
+                width,
+                height,
+                frames,
+            };
+            print!("{}", bench.run(count, seed(&maze))?);
+            Ok(())
         }
-        let _ = writer_thread.join();
     }
-    drop(_screen_state);
+}
+
+fn run(args: RunArgs) -> io::Result<()> {
+    let app = App {
+        generator: args.maze.generator,
+        width: args.maze.width,
+        height: args.maze.height,
+        solver: args.solver,
+        race: args.race,
+        render: args.render,
+        heatmap: args.heatmap,
+        fps: args.fps,
+        frame_stats: args.frame_stats,
+        record: args.record,
+        theme: args.theme,
+        theme_file: args.theme_file,
+        color_depth: args.color_depth,
+        loop_mazes: args.loop_mazes,
+    };
+    let seed = app.run(seed(&args.maze))?;
+    println!("seed: {seed}");
     Ok(())
 }
 
-fn writer_thread(maze_thread: JoinHandle<()>, maze: RandomMaze) -> std::io::Result<()> {
-    let mut stdout = stdout();
-    let (width, height) = terminal::size()?;
-    let mut buf = Buffer::new(width as usize, height as usize);
-    while !maze_thread.is_finished() {
-        std::thread::sleep(Duration::from_millis(1));
-        let grid = maze.grid.lock().unwrap();
-        let _ = buf.flush_diff(&mut stdout, &grid);
+fn run_headless(args: RunArgs) -> io::Result<()> {
+    let (width, height) = grid_size(&args.maze)?;
+    let first_seed = seed(&args.maze);
+    #[cfg(feature = "serde")]
+    let format = match args.json {
+        true => OutputFormat::Json,
+        false => OutputFormat::Text,
+    };
+    #[cfg(not(feature = "serde"))]
+    let format = OutputFormat::Text;
+    let headless = Headless {
+        generator: args.maze.generator,
+        solver: args.solver,
+        race: args.race,
+        width,
+        height,
+    };
+    let mut out = stdout().lock();
+    for i in 0..args.count {
+        headless.write(&mut out, first_seed.wrapping_add(i as u64), format)?;
     }
-    thread::sleep(Duration::from_millis(15));
-    let _ = maze_thread.join();
     Ok(())
 }
+
+/// The seed asked for on the command line, or a random one.
+fn seed(maze: &MazeArgs) -> u64 {
+    maze.seed.unwrap_or_else(|| rand::thread_rng().gen())
+}
+
+/// Grid size requested on the command line, falling back to the terminal size.
+fn grid_size(maze: &MazeArgs) -> io::Result<(usize, usize)> {
+    match (maze.width, maze.height) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        (width, height) => {
+            let (term_width, term_height) = terminal::size().map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("can't read the terminal size ({err}), pass --width and --height"),
+                )
+            })?;
+            Ok((
+                width.unwrap_or(term_width as usize),
+                height.unwrap_or(term_height as usize),
+            ))
+        }
+    }
+}
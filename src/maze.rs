@@ -1,37 +1,120 @@
 use std::{
-    collections::VecDeque,
-    sync::{Arc, Mutex},
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
     thread,
     time::Duration,
 };
 
 use crate::point::Point;
 use crate::{grid::Grid, space::Space};
-use rand::{seq::IteratorRandom, Rng};
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
+
+/// The maze generators that can be picked by name.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum GeneratorKind {
+    /// Obstacles scattered uniformly at random.
+    #[default]
+    Random,
+}
+
+impl GeneratorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GeneratorKind::Random => "random",
+        }
+    }
+
+    /// Builds a maze into `grid` from `seed`, or `None` if the grid is too small
+    /// to hold a start and an end.
+    pub fn build(&self, grid: Grid, seed: u64) -> Option<RandomMaze> {
+        let grid = Arc::new(Mutex::new(grid));
+        match self {
+            GeneratorKind::Random => {
+                let mut maze = RandomMaze::new(grid, seed);
+                maze.build_maze()?;
+                Some(maze)
+            }
+        }
+    }
+}
+
+/// The solvers that can be picked by name.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SolverKind {
+    /// Breadth-first search.
+    #[default]
+    Bfs,
+    /// Depth-first search.
+    Dfs,
+    /// A* search with the Manhattan distance to the end.
+    AStar,
+}
+
+impl SolverKind {
+    pub const ALL: [SolverKind; 3] = [SolverKind::Bfs, SolverKind::Dfs, SolverKind::AStar];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolverKind::Bfs => "bfs",
+            SolverKind::Dfs => "dfs",
+            SolverKind::AStar => "a-star",
+        }
+    }
+
+    pub fn solve(
+        &self,
+        start: Point,
+        grid: Arc<Mutex<Grid>>,
+        delay: Duration,
+        progress: &Progress,
+    ) -> Solution {
+        match self {
+            SolverKind::Bfs => bfs(start, grid, delay, progress),
+            SolverKind::Dfs => dfs(start, grid, delay, progress),
+            SolverKind::AStar => a_star(start, grid, delay, progress),
+        }
+    }
+}
 
 pub enum Orientation {
     Horz,
     Vert,
 }
 
+/// Scatters obstacles over the grid and picks a start and end. All randomness
+/// comes from an RNG seeded with `seed`, so the same seed and grid size always
+/// produce the same maze.
 pub struct RandomMaze {
     pub grid: Arc<Mutex<Grid>>,
     pub start: Point,
     pub end: Point,
+    pub seed: u64,
+    /// Every space set while building, in order, starting from an empty grid.
+    pub changes: Vec<Change>,
+    rng: StdRng,
 }
 
 impl RandomMaze {
-    pub fn new(grid: Arc<Mutex<Grid>>) -> Self {
+    pub fn new(grid: Arc<Mutex<Grid>>, seed: u64) -> Self {
         Self {
             grid,
             start: Point::default(),
             end: Point::default(),
+            seed,
+            changes: vec![],
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 
     fn randomize_start(&mut self) -> Option<()> {
         let mut grid = self.grid.lock().unwrap();
-        let mut rng = rand::thread_rng();
 
         let start = (0..grid.width * grid.height)
             .filter_map(|index| {
@@ -41,16 +124,20 @@ impl RandomMaze {
                     _ => None,
                 }
             })
-            .choose(&mut rng)?;
+            .choose(&mut self.rng)?;
 
         *grid.get_mut(start).unwrap() = Space::Start(start);
+        self.changes.push(Change {
+            point: start,
+            space: Space::Start(start),
+            from: None,
+        });
         self.start = start;
         Some(())
     }
 
     fn randomize_end(&mut self) -> Option<()> {
         let mut grid = self.grid.lock().unwrap();
-        let mut rng = rand::thread_rng();
 
         let end = (0..grid.width * grid.height)
             .filter_map(|index| {
@@ -60,8 +147,13 @@ impl RandomMaze {
                     _ => None,
                 }
             })
-            .choose(&mut rng)?;
+            .choose(&mut self.rng)?;
         *grid.get_mut(end).unwrap() = Space::End(end);
+        self.changes.push(Change {
+            point: end,
+            space: Space::End(end),
+            from: None,
+        });
         self.end = end;
         Some(())
     }
@@ -69,10 +161,16 @@ impl RandomMaze {
     fn randomize_obstacles(&mut self) {
         let mut grid = self.grid.lock().unwrap();
         grid.clear();
-        let mut rng = rand::thread_rng();
-        for space in grid.spaces.iter_mut() {
-            if rng.gen_bool(0.2) {
+        self.changes.clear();
+        let width = grid.width;
+        for (index, space) in grid.spaces.iter_mut().enumerate() {
+            if self.rng.gen_bool(0.2) {
                 *space = Space::Obstacle;
+                self.changes.push(Change {
+                    point: Point::from_index(index, width),
+                    space: Space::Obstacle,
+                    from: None,
+                });
             }
         }
     }
@@ -85,19 +183,168 @@ impl RandomMaze {
     }
 }
 
-pub fn bfs(start: Point, grid: Arc<Mutex<Grid>>) -> Option<()> {
+/// Cells discovered by a solver, in the order they were reached, and the path it
+/// found from start to end (inclusive). `path` is empty when the end is unreachable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Solution {
+    pub visited: Vec<Point>,
+    pub path: Vec<Point>,
+}
+
+/// Counters a solver keeps up to date while it runs, so they can be shown
+/// before it finishes. It also carries flags asking the solver to pause or
+/// stop early, and sends the spaces it changes to a subscriber if there is one.
+#[derive(Debug, Default)]
+pub struct Progress {
+    expanded: AtomicUsize,
+    frontier: AtomicUsize,
+    path_len: AtomicUsize,
+    current: Mutex<Option<Point>>,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    events: Mutex<Option<Sender<Change>>>,
+}
+
+/// A space a generator or solver set in the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    pub point: Point,
+    pub space: Space,
+    /// The cell a solver reached `point` from, when this change puts it on
+    /// the frontier.
+    pub from: Option<Point>,
+}
+
+/// A reading of [`Progress`] at one moment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Cells taken off the frontier and expanded.
+    pub expanded: usize,
+    /// Cells discovered but not expanded yet.
+    pub frontier: usize,
+    /// Cells on the path, start and end included, or 0 until one is found.
+    pub path_len: usize,
+    /// The cell expanded most recently, where the search is working now.
+    pub current: Option<Point>,
+    pub paused: bool,
+}
+
+impl Progress {
+    pub fn stats(&self) -> Stats {
+        Stats {
+            expanded: self.expanded.load(Ordering::Relaxed),
+            frontier: self.frontier.load(Ordering::Relaxed),
+            path_len: self.path_len.load(Ordering::Relaxed),
+            current: *self.current.lock().unwrap(),
+            paused: self.is_paused(),
+        }
+    }
+
+    /// Holds the solver before its next step until it is resumed, or lets it
+    /// carry on.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Asks the solver to give up at its next step. It returns what it found
+    /// so far, with no path unless the path was already complete.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Sends every change the solver makes to the grid from now on to the
+    /// returned receiver, so a copy of the grid can be kept up to date without
+    /// locking or rescanning it. Replaces any earlier subscriber.
+    pub fn subscribe(&self) -> Receiver<Change> {
+        let (sender, receiver) = mpsc::channel();
+        *self.events.lock().unwrap() = Some(sender);
+        receiver
+    }
+
+    /// Sets `point` in `grid` to `space` and tells the subscriber.
+    fn set(&self, grid: &mut Grid, point: Point, space: Space) {
+        self.apply(
+            grid,
+            Change {
+                point,
+                space,
+                from: None,
+            },
+        );
+    }
+
+    /// Puts `point` on the frontier `distance` steps from the start, reached
+    /// from `from`.
+    fn discover(&self, grid: &mut Grid, point: Point, distance: u32, from: Point) {
+        let space = Space::Frontier(distance);
+        self.apply(
+            grid,
+            Change {
+                point,
+                space,
+                from: Some(from),
+            },
+        );
+    }
+
+    /// Makes `change` in `grid` and tells the subscriber.
+    fn apply(&self, grid: &mut Grid, change: Change) {
+        if let Some(slot) = grid.get_mut(change.point) {
+            *slot = change.space;
+        }
+        if let Some(events) = self.events.lock().unwrap().as_ref() {
+            // The subscriber may have gone away, which is fine.
+            let _ = events.send(change);
+        }
+    }
+
+    /// Marks `point` visited as it is expanded, if it was on the frontier.
+    /// The start is never on it, so it keeps its look.
+    fn close(&self, grid: &mut Grid, point: Point) {
+        if let Some(Space::Frontier(distance)) = grid.get(point) {
+            self.set(grid, point, Space::Visited(distance));
+        }
+    }
+
+    fn expand(&self, current: Point, frontier: usize) {
+        *self.current.lock().unwrap() = Some(current);
+        self.expanded.fetch_add(1, Ordering::Relaxed);
+        self.frontier.store(frontier, Ordering::Relaxed);
+    }
+}
+
+/// Breadth-first search from `start`, marking cells in `grid` as it goes:
+/// [`Space::Frontier`] when they are queued and [`Space::Visited`] once they
+/// are expanded. `delay` is slept between steps so the search can be watched;
+/// pass `Duration::ZERO` to solve as fast as possible. Counts are kept in
+/// `progress`.
+pub fn bfs(start: Point, grid: Arc<Mutex<Grid>>, delay: Duration, progress: &Progress) -> Solution {
     let len = grid.lock().unwrap().spaces.len();
     let mut queue = VecDeque::from([start]);
     let mut pred = vec![0; len];
+    let mut distance = vec![0; len];
 
     let mut end = None;
 
     let mut visited_path = vec![];
 
     'outer: while !queue.is_empty() {
-        thread::sleep(Duration::from_millis(1));
+        pause(delay, progress);
+        if progress.is_cancelled() {
+            break;
+        }
         let mut data = grid.lock().unwrap();
         let current = queue.pop_front().unwrap();
+        progress.expand(current, queue.len());
+        progress.close(&mut data, current);
         let mut empty_adj: VecDeque<Point> = data
             .adjacent_points(current)
             .into_iter()
@@ -113,33 +360,201 @@ pub fn bfs(start: Point, grid: Arc<Mutex<Grid>>) -> Option<()> {
             visited_path.push(*adjacent);
             let adjacent_index = data.unchecked_index(*adjacent);
             pred[adjacent_index] = parent_index;
+            distance[adjacent_index] = distance[parent_index] + 1;
             if let Some(Space::End(_)) = data.get(*adjacent) {
                 end = Some(adjacent_index);
                 break 'outer;
             } else {
-                *data.get_mut(*adjacent).unwrap() = Space::Visited;
+                progress.discover(&mut data, *adjacent, distance[adjacent_index], current);
             }
         }
         queue.append(&mut empty_adj);
+        progress.frontier.store(queue.len(), Ordering::Relaxed);
+    }
+
+    trace_path(&grid, &pred, end, visited_path, delay, progress)
+}
+
+/// Depth-first search from `start`: it always carries on from the cell found
+/// most recently, so it runs down one corridor at a time and the path it finds
+/// is rarely the shortest. Marks cells and counts like [`bfs`].
+pub fn dfs(start: Point, grid: Arc<Mutex<Grid>>, delay: Duration, progress: &Progress) -> Solution {
+    let len = grid.lock().unwrap().spaces.len();
+    let mut stack = vec![start];
+    let mut pred = vec![0; len];
+    let mut distance = vec![0; len];
+
+    let mut end = None;
+
+    let mut visited_path = vec![];
+
+    'outer: while let Some(current) = stack.pop() {
+        pause(delay, progress);
+        if progress.is_cancelled() {
+            break;
+        }
+        let mut data = grid.lock().unwrap();
+        progress.expand(current, stack.len());
+        progress.close(&mut data, current);
+        let parent_index = data.unchecked_index(current);
+
+        for adjacent in data.adjacent_points(current) {
+            if !data.get(adjacent).is_some_and(|space| space.is_pathable()) {
+                continue;
+            }
+            visited_path.push(adjacent);
+            let adjacent_index = data.unchecked_index(adjacent);
+            pred[adjacent_index] = parent_index;
+            distance[adjacent_index] = distance[parent_index] + 1;
+            if let Some(Space::End(_)) = data.get(adjacent) {
+                end = Some(adjacent_index);
+                break 'outer;
+            }
+            progress.discover(&mut data, adjacent, distance[adjacent_index], current);
+            stack.push(adjacent);
+        }
+        progress.frontier.store(stack.len(), Ordering::Relaxed);
+    }
+
+    trace_path(&grid, &pred, end, visited_path, delay, progress)
+}
+
+/// A* search from `start` to the grid's end. It expands the cell with the
+/// lowest distance so far plus the Manhattan distance still to go, so it finds
+/// a shortest path like [`bfs`] while usually expanding far fewer cells.
+/// Marks cells and counts like [`bfs`].
+pub fn a_star(
+    start: Point,
+    grid: Arc<Mutex<Grid>>,
+    delay: Duration,
+    progress: &Progress,
+) -> Solution {
+    let (len, width, goal) = {
+        let data = grid.lock().unwrap();
+        (data.spaces.len(), data.width, data.end())
+    };
+    let Some(goal) = goal else {
+        return Solution::default();
+    };
+    let estimate = |point: Point| point.x.abs_diff(goal.x) + point.y.abs_diff(goal.y);
+
+    // Ties on the total go to the cell closer to the end, which keeps the
+    // search heading for it instead of widening.
+    let start_index = start.index(width);
+    let mut open = BinaryHeap::from([Reverse((estimate(start), estimate(start), start_index))]);
+    let mut distance = vec![u32::MAX; len];
+    distance[start_index] = 0;
+    let mut closed = vec![false; len];
+    let mut pred = vec![0; len];
+
+    let mut end = None;
+
+    let mut visited_path = vec![];
+
+    while let Some(Reverse((_, _, index))) = open.pop() {
+        if closed[index] {
+            continue;
+        }
+        pause(delay, progress);
+        if progress.is_cancelled() {
+            break;
+        }
+        closed[index] = true;
+        let current = Point::from_index(index, width);
+        let mut data = grid.lock().unwrap();
+        progress.expand(current, open.len());
+        progress.close(&mut data, current);
+        if current == goal {
+            end = Some(index);
+            break;
+        }
+
+        for adjacent in data.adjacent_points(current) {
+            let adjacent_index = data.unchecked_index(adjacent);
+            let open_space = matches!(
+                data.get(adjacent),
+                Some(Space::Empty | Space::End(_) | Space::Frontier(_))
+            );
+            let through_current = distance[index] + 1;
+            if !open_space || closed[adjacent_index] || through_current >= distance[adjacent_index]
+            {
+                continue;
+            }
+            if distance[adjacent_index] == u32::MAX {
+                visited_path.push(adjacent);
+            }
+            distance[adjacent_index] = through_current;
+            pred[adjacent_index] = index;
+            if adjacent != goal {
+                progress.discover(&mut data, adjacent, through_current, current);
+            }
+            let remaining = estimate(adjacent);
+            open.push(Reverse((
+                through_current as usize + remaining,
+                remaining,
+                adjacent_index,
+            )));
+        }
+        progress.frontier.store(open.len(), Ordering::Relaxed);
     }
 
-    let mut path: Vec<usize> = Vec::new();
+    trace_path(&grid, &pred, end, visited_path, delay, progress)
+}
+
+/// Follows `pred` back from `end` to the start and marks the path on the grid
+/// a cell at a time. There is no path when `end` is `None`.
+fn trace_path(
+    grid: &Mutex<Grid>,
+    pred: &[usize],
+    end: Option<usize>,
+    visited: Vec<Point>,
+    delay: Duration,
+    progress: &Progress,
+) -> Solution {
+    let Some(end) = end else {
+        return Solution {
+            visited,
+            path: vec![],
+        };
+    };
+    let width = grid.lock().unwrap().width;
 
-    let end = end?;
+    let mut path: Vec<usize> = vec![end];
     let mut crawl = pred[end];
-    // let data = grid.lock().unwrap();
     while !matches!(grid.lock().unwrap().spaces[crawl], Space::Start(_)) {
         path.push(crawl);
         crawl = pred[crawl];
     }
-    // drop(data);
+    path.push(crawl);
+    path.reverse();
+    progress.path_len.store(path.len(), Ordering::Relaxed);
 
-    for value in path {
-        thread::sleep(Duration::from_millis(10));
+    for &value in &path[1..path.len() - 1] {
+        if progress.is_cancelled() {
+            break;
+        }
+        pause(delay * 10, progress);
         let mut data = grid.lock().unwrap();
-        data.spaces[value] = Space::Path;
+        progress.set(&mut data, Point::from_index(value, width), Space::Path);
+    }
+
+    Solution {
+        visited,
+        path: path
+            .into_iter()
+            .map(|index| Point::from_index(index, width))
+            .collect(),
+    }
+}
+
+/// Sleeps for `delay`, then for as long as `progress` is paused.
+fn pause(delay: Duration, progress: &Progress) {
+    if !delay.is_zero() {
+        thread::sleep(delay);
+    }
+    while progress.is_paused() && !progress.is_cancelled() {
+        thread::sleep(Duration::from_millis(10));
     }
-    Some(())
 }
 
 // Assignment4_Tests RadomMazeBuilder
@@ -150,6 +565,8 @@ mod random_maze_tests {
         thread,
     };
 
+    use rand::{thread_rng, Rng};
+
     use crate::grid::Grid;
 
     use super::RandomMaze;
@@ -163,7 +580,7 @@ mod random_maze_tests {
                     let width = 10;
                     let height = 10;
                     let grid = Arc::new(Mutex::new(Grid::new(width, height)));
-                    let mut random_maze = RandomMaze::new(grid);
+                    let mut random_maze = RandomMaze::new(grid, thread_rng().gen());
                     let _ = random_maze.build_maze();
                 })
                 .join()
@@ -173,4 +590,216 @@ mod random_maze_tests {
                 assert!(results.is_some());
             });
     }
+
+    #[test]
+    fn manual_test_same_seed_same_maze() {
+        let build = |seed| {
+            let grid = Arc::new(Mutex::new(Grid::new(20, 10)));
+            let mut random_maze = RandomMaze::new(grid, seed);
+            random_maze.build_maze();
+            let spaces = random_maze.grid.lock().unwrap().spaces.clone();
+            (random_maze.start, random_maze.end, spaces)
+        };
+
+        assert_eq!(build(1234), build(1234));
+        assert_ne!(build(1234), build(4321));
+    }
+}
+
+#[cfg(test)]
+mod bfs_tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use super::{bfs, Progress, Stats};
+    use crate::{grid::Grid, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_bfs_path() {
+        // S . #
+        // # . E
+        let start = Point::new(0, 0);
+        let end = Point::new(2, 1);
+        let mut grid = Grid::new(3, 2);
+        *grid.get_mut(start).unwrap() = Space::Start(start);
+        *grid.get_mut(end).unwrap() = Space::End(end);
+        *grid.get_mut(Point::new(2, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(0, 1)).unwrap() = Space::Obstacle;
+        let grid = Arc::new(Mutex::new(grid));
+
+        let progress = Progress::default();
+        let solution = bfs(start, grid.clone(), Duration::ZERO, &progress);
+
+        let expected = vec![start, Point::new(1, 0), Point::new(1, 1), end];
+        assert_eq!(expected, solution.path);
+        let stats = Stats {
+            expanded: 3,
+            frontier: 0,
+            path_len: 4,
+            current: Some(Point::new(1, 1)),
+            paused: false,
+        };
+        assert_eq!(stats, progress.stats());
+        assert_eq!(Some(&end), solution.visited.last());
+        let grid = grid.lock().unwrap();
+        assert_eq!(Some(Space::Path), grid.get(Point::new(1, 1)));
+        assert_eq!(Some(Space::End(end)), grid.get(end));
+    }
+
+    #[test]
+    fn manual_test_bfs_unreachable() {
+        let start = Point::new(0, 0);
+        let end = Point::new(2, 0);
+        let mut grid = Grid::new(3, 1);
+        *grid.get_mut(start).unwrap() = Space::Start(start);
+        *grid.get_mut(Point::new(1, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(end).unwrap() = Space::End(end);
+
+        let solution = bfs(
+            start,
+            Arc::new(Mutex::new(grid)),
+            Duration::ZERO,
+            &Progress::default(),
+        );
+
+        assert!(solution.path.is_empty());
+        assert!(solution.visited.is_empty());
+    }
+
+    #[test]
+    fn manual_test_bfs_cancelled() {
+        let start = Point::new(0, 0);
+        let end = Point::new(2, 0);
+        let mut grid = Grid::new(3, 1);
+        *grid.get_mut(start).unwrap() = Space::Start(start);
+        *grid.get_mut(end).unwrap() = Space::End(end);
+
+        let progress = Progress::default();
+        progress.cancel();
+        let solution = bfs(start, Arc::new(Mutex::new(grid)), Duration::ZERO, &progress);
+
+        assert!(solution.path.is_empty());
+        assert_eq!(0, progress.stats().expanded);
+    }
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use super::{GeneratorKind, Progress, SolverKind};
+    use crate::{grid::Grid, point::Point, space::Space};
+
+    /// Every step of `path` moves to a neighbouring cell.
+    fn is_connected(path: &[Point]) -> bool {
+        path.windows(2)
+            .all(|pair| pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y) == 1)
+    }
+
+    #[test]
+    fn fuzzy_test_solvers_agree_on_solvability() {
+        for seed in 0..40 {
+            let mut lengths = vec![];
+            for solver in SolverKind::ALL {
+                let maze = GeneratorKind::Random.build(Grid::new(12, 9), seed).unwrap();
+                let progress = Progress::default();
+                let solution = solver.solve(maze.start, maze.grid, Duration::ZERO, &progress);
+
+                if let (Some(first), Some(last)) = (solution.path.first(), solution.path.last()) {
+                    assert_eq!((maze.start, maze.end), (*first, *last), "{}", solver.name());
+                    assert!(is_connected(&solution.path), "{}", solver.name());
+                }
+                assert_eq!(solution.path.len(), progress.stats().path_len);
+                lengths.push(solution.path.len());
+            }
+            let [bfs, dfs, a_star] = lengths[..] else {
+                unreachable!()
+            };
+            // A* finds a shortest path just like BFS does.
+            assert_eq!(bfs, a_star, "seed {seed}");
+            assert_eq!(bfs == 0, dfs == 0, "seed {seed}");
+            assert!(dfs >= bfs, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn manual_test_pause_holds_the_solver() {
+        let maze = GeneratorKind::Random.build(Grid::new(12, 9), 1).unwrap();
+        let progress = Arc::new(Progress::default());
+        progress.set_paused(true);
+        let solver = {
+            let progress = progress.clone();
+            thread::spawn(move || {
+                SolverKind::Bfs.solve(maze.start, maze.grid, Duration::ZERO, &progress)
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(0, progress.stats().expanded);
+        assert!(progress.stats().paused);
+
+        progress.set_paused(false);
+        solver.join().unwrap();
+        assert!(progress.stats().expanded > 0);
+    }
+
+    #[test]
+    fn fuzzy_test_cells_join_the_frontier_before_they_are_visited() {
+        for seed in 0..10 {
+            for solver in SolverKind::ALL {
+                let maze = GeneratorKind::Random.build(Grid::new(12, 9), seed).unwrap();
+                let mut copy = maze.grid.lock().unwrap().clone();
+                let progress = Progress::default();
+                let events = progress.subscribe();
+                let solution =
+                    solver.solve(maze.start, maze.grid.clone(), Duration::ZERO, &progress);
+
+                let mut visited = 0;
+                for change in events.try_iter() {
+                    let before = copy.get(change.point).unwrap();
+                    if let Space::Visited(distance) = change.space {
+                        assert_eq!(Space::Frontier(distance), before, "{}", solver.name());
+                        visited += 1;
+                    }
+                    *copy.get_mut(change.point).unwrap() = change.space;
+                }
+                // Everything expanded was on the frontier first, bar the start
+                // and the end, which A* expands when it gets there.
+                let end_expanded = solver == SolverKind::AStar && !solution.path.is_empty();
+                let expanded = progress.stats().expanded;
+                assert_eq!(expanded - 1 - end_expanded as usize, visited, "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn fuzzy_test_changes_replay_the_generator() {
+        for seed in 0..10 {
+            let maze = GeneratorKind::Random.build(Grid::new(12, 9), seed).unwrap();
+            let mut copy = Grid::new(12, 9);
+            for change in &maze.changes {
+                *copy.get_mut(change.point).unwrap() = change.space;
+            }
+            assert_eq!(maze.grid.lock().unwrap().spaces, copy.spaces, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn fuzzy_test_changes_replay_the_solve() {
+        for seed in 0..10 {
+            for solver in SolverKind::ALL {
+                let maze = GeneratorKind::Random.build(Grid::new(12, 9), seed).unwrap();
+                let mut copy = maze.grid.lock().unwrap().clone();
+                let progress = Progress::default();
+                let events = progress.subscribe();
+                solver.solve(maze.start, maze.grid.clone(), Duration::ZERO, &progress);
+
+                for change in events.try_iter() {
+                    *copy.get_mut(change.point).unwrap() = change.space;
+                }
+                assert_eq!(maze.grid.lock().unwrap().spaces, copy.spaces, "seed {seed}");
+            }
+        }
+    }
 }
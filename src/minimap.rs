@@ -0,0 +1,338 @@
+use crossterm::style::{Color, Colors};
+
+use crate::buffer::{Buffer, Cell};
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::space::Space;
+
+/// A thumbnail of the whole grid for a corner of the screen, drawn with half
+/// blocks so each character holds two pixels. Each pixel stands for a square
+/// block of the grid and is painted like the space that matters most in it:
+/// the start or end, then the path, then the search, and otherwise obstacle
+/// if at least half the block is. Colours come from the buffer's theme as it
+/// is drawn, so switching theme recolours it too.
+#[derive(Debug, Clone)]
+pub struct Minimap {
+    /// Grid cells along each side of a pixel.
+    scale: usize,
+    /// Size in pixels.
+    width: usize,
+    height: usize,
+    pixels: Vec<Space>,
+    /// The pixel row the next [`Minimap::refresh`] starts from.
+    next_row: usize,
+    /// Which pixel rows to recompute before the minimap is up to date.
+    stale: Vec<bool>,
+}
+
+/// What a pixel of the minimap shows once drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pixel {
+    Space(Space),
+    /// The edge of the part of the grid on screen.
+    Outline,
+    /// Below the last pixel row, in the bottom half of the last character.
+    Blank,
+}
+
+impl Minimap {
+    /// The largest minimap, in characters.
+    pub const MAX_WIDTH: usize = 24;
+    pub const MAX_HEIGHT: usize = 8;
+
+    /// The viewport outline, in the terminal's own text colour.
+    const OUTLINE: Color = Color::Reset;
+    /// The viewport outline for themes without colour.
+    const OUTLINE_GLYPH: char = ':';
+
+    /// A minimap for a `grid_width` by `grid_height` grid, scaled down to fit
+    /// [`Minimap::MAX_WIDTH`] by [`Minimap::MAX_HEIGHT`] and keeping its
+    /// shape. It starts out blank until refreshed.
+    pub fn new(grid_width: usize, grid_height: usize) -> Self {
+        let scale = grid_width
+            .div_ceil(Self::MAX_WIDTH)
+            .max(grid_height.div_ceil(Self::MAX_HEIGHT * 2))
+            .max(1);
+        let (width, height) = (grid_width.div_ceil(scale), grid_height.div_ceil(scale));
+        Self {
+            scale,
+            width,
+            height,
+            pixels: vec![Space::Empty; width * height],
+            next_row: 0,
+            stale: vec![true; height],
+        }
+    }
+
+    /// Size in characters.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height.div_ceil(2))
+    }
+
+    /// Notes that the grid changed at `point`, so refreshing goes over the
+    /// pixel row it falls in once more.
+    pub fn mark_changed(&mut self, point: Point) {
+        if let Some(stale) = self.stale.get_mut(point.y / self.scale) {
+            *stale = true;
+        }
+    }
+
+    /// Recomputes stale pixel rows, carrying on from where the last call
+    /// stopped, until about `budget` grid cells have been read. A big grid is
+    /// scanned a slice per frame this way, rather than all of it every frame.
+    /// Once every row is up to date it does nothing until
+    /// [`Minimap::mark_changed`].
+    pub fn refresh(&mut self, grid: &Grid, budget: usize) {
+        let row_cost = (self.scale * grid.width).max(1);
+        let mut rows = (budget / row_cost).max(1);
+        for _ in 0..self.height {
+            if rows == 0 {
+                break;
+            }
+            let y = self.next_row;
+            self.next_row = (y + 1) % self.height;
+            if !self.stale[y] {
+                continue;
+            }
+            self.stale[y] = false;
+            for x in 0..self.width {
+                self.pixels[y * self.width + x] = self.pixel(grid, x, y);
+            }
+            rows -= 1;
+        }
+    }
+
+    /// Whether some rows are still waiting for [`Minimap::refresh`].
+    pub fn is_stale(&self) -> bool {
+        self.stale.contains(&true)
+    }
+
+    /// Recomputes every pixel.
+    pub fn refresh_all(&mut self, grid: &Grid) {
+        self.next_row = 0;
+        self.stale.fill(true);
+        self.refresh(grid, usize::MAX);
+    }
+
+    /// How much `space` matters when a pixel or character can only show one.
+    fn rank(space: Space) -> usize {
+        match space {
+            Space::Start(_) | Space::End(_) => 5,
+            Space::Path => 4,
+            Space::Frontier(_) => 3,
+            Space::Visited(_) => 2,
+            Space::Obstacle => 1,
+            Space::Empty => 0,
+        }
+    }
+
+    fn pixel(&self, grid: &Grid, x: usize, y: usize) -> Space {
+        let (mut obstacles, mut cells) = (0, 0);
+        let mut found = Space::Empty;
+        for block_y in y * self.scale..((y + 1) * self.scale).min(grid.height) {
+            for block_x in x * self.scale..((x + 1) * self.scale).min(grid.width) {
+                let space = grid.spaces[Point::new(block_x, block_y).index(grid.width)];
+                if space == Space::Obstacle {
+                    obstacles += 1;
+                } else if Self::rank(space) > Self::rank(found) {
+                    found = space;
+                }
+                cells += 1;
+            }
+        }
+        match found {
+            Space::Empty if obstacles * 2 >= cells && cells > 0 => Space::Obstacle,
+            found => found,
+        }
+    }
+
+    /// Draws the minimap into `buf` with its top-left corner at `x`, `y`,
+    /// outlining `visible`, the part of the grid on screen as returned by
+    /// [`Buffer::visible`]. Pixels are painted with [`Buffer::paint`], or for
+    /// a theme without colour a character shows the glyph of whichever of its
+    /// two pixels matters most.
+    pub fn draw(
+        &self,
+        buf: &mut Buffer,
+        x: usize,
+        y: usize,
+        visible: (isize, isize, usize, usize),
+    ) {
+        let (left, top, width, height) = visible;
+        let scale = self.scale as isize;
+        let span = |start: isize, len: usize, max: usize| {
+            let first = start.max(0) / scale;
+            let last = (start + len as isize - 1).div_euclid(scale);
+            (first, last.min(max as isize - 1))
+        };
+        let (x0, x1) = span(left, width, self.width);
+        let (y0, y1) = span(top, height, self.height);
+
+        let pixel = |px: usize, py: usize| {
+            if py >= self.height {
+                return Pixel::Blank;
+            }
+            let (px, py) = (px as isize, py as isize);
+            let inside = (x0..=x1).contains(&px) && (y0..=y1).contains(&py);
+            let edge = px == x0 || px == x1 || py == y0 || py == y1;
+            match inside && edge {
+                true => Pixel::Outline,
+                false => Pixel::Space(self.pixels[py as usize * self.width + px as usize]),
+            }
+        };
+        let glyphs = [Space::Obstacle, Space::Path]
+            .into_iter()
+            .all(|space| buf.paint(space).block_color().is_none());
+        let (chars_wide, chars_high) = self.size();
+        for row in 0..chars_high {
+            for column in 0..chars_wide {
+                let pixels = (pixel(column, row * 2), pixel(column, row * 2 + 1));
+                let cell = match glyphs {
+                    true => Self::glyph(buf, pixels),
+                    false => Self::half_block(buf, pixels),
+                };
+                buf.put(x + column, y + row, cell.ch, cell.colors);
+            }
+        }
+    }
+
+    /// A character showing the `top` and `bottom` pixels in colour.
+    fn half_block(buf: &Buffer, (top, bottom): (Pixel, Pixel)) -> Cell {
+        let color = |pixel| match pixel {
+            Pixel::Space(space) => buf.paint(space).block_color(),
+            Pixel::Outline | Pixel::Blank => None,
+        };
+        let (ch, background) = match (top, bottom) {
+            (Pixel::Outline, Pixel::Outline) => ('█', None),
+            (Pixel::Outline, other) => ('▀', color(other)),
+            (other, Pixel::Outline) => ('▄', color(other)),
+            (top, bottom) => {
+                let paint = |pixel| match pixel {
+                    Pixel::Space(space) => buf.paint(space),
+                    _ => buf.paint(Space::Empty),
+                };
+                return Cell::half_block(paint(top), paint(bottom));
+            }
+        };
+        Cell {
+            ch,
+            colors: Colors {
+                foreground: Some(Self::OUTLINE),
+                background,
+            },
+        }
+    }
+
+    /// A character showing whichever of two pixels matters most by its glyph.
+    fn glyph(buf: &Buffer, (top, bottom): (Pixel, Pixel)) -> Cell {
+        let space = |pixel| match pixel {
+            Pixel::Space(space) => Some(space),
+            Pixel::Outline | Pixel::Blank => None,
+        };
+        if top == Pixel::Outline || bottom == Pixel::Outline {
+            return Cell {
+                ch: Self::OUTLINE_GLYPH,
+                colors: Colors {
+                    foreground: None,
+                    background: None,
+                },
+            };
+        }
+        let space = match (space(top), space(bottom)) {
+            (Some(top), Some(bottom)) if Self::rank(bottom) > Self::rank(top) => bottom,
+            (Some(top), _) => top,
+            (None, bottom) => bottom.unwrap_or(Space::Empty),
+        };
+        buf.paint(space)
+    }
+}
+
+#[cfg(test)]
+mod minimap_tests {
+    use super::Minimap;
+    use crate::buffer::{Buffer, Cell};
+    use crate::theme::{ThemeKind, DEFAULT_THEME};
+    use crate::{grid::Grid, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_scaled_to_fit() {
+        assert_eq!((10, 3), Minimap::new(10, 5).size());
+        assert_eq!((24, 2), Minimap::new(2000, 200).size());
+        assert_eq!((8, 8), Minimap::new(1000, 2000).size());
+    }
+
+    #[test]
+    fn manual_test_draw() {
+        // Each pixel covers a 2x2 block once the grid is too tall to fit.
+        let mut grid = Grid::new(4, 32);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(1, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(3, 31)).unwrap() = Space::Path;
+
+        let mut minimap = Minimap::new(4, 32);
+        assert_eq!((2, 8), minimap.size());
+        minimap.refresh_all(&grid);
+
+        let mut buf = Buffer::new(2, 10);
+        minimap.draw(&mut buf, 0, 1, (0, 0, 4, 4));
+
+        // The viewport covers the top two pixel rows of both columns.
+        let top = buf.cells[2];
+        assert_eq!('█', top.ch);
+        assert_eq!(Some(Minimap::OUTLINE), top.colors.foreground);
+
+        // Half of the top-left block is obstacle, and the path is in the
+        // bottom-right one, both in the theme's colours.
+        minimap.draw(&mut buf, 0, 1, (0, 10, 4, 4));
+        let obstacle = DEFAULT_THEME.obstacle.block_color();
+        assert_eq!(obstacle, buf.cells[2].colors.foreground);
+        let path = Cell::half_block(DEFAULT_THEME.empty, DEFAULT_THEME.path);
+        assert_eq!(path, buf.cells[2 * 8 + 1]);
+
+        // A theme switch recolours it, and one without colour shows glyphs.
+        buf.set_theme(ThemeKind::Solarized.theme());
+        minimap.draw(&mut buf, 0, 1, (0, 10, 4, 4));
+        let solarized = ThemeKind::Solarized.theme();
+        let path = Cell::half_block(solarized.empty, solarized.path);
+        assert_eq!(path, buf.cells[2 * 8 + 1]);
+
+        buf.set_theme(ThemeKind::Ascii.theme());
+        minimap.draw(&mut buf, 0, 1, (0, 10, 4, 4));
+        assert_eq!('#', buf.cells[2].ch);
+        assert_eq!('*', buf.cells[2 * 8 + 1].ch);
+        assert_eq!(':', buf.cells[2 * 3].ch);
+        let colors = buf.cells[2..2 * 9].iter().map(|cell| cell.colors);
+        assert!(colors
+            .flat_map(|colors| [colors.foreground, colors.background])
+            .all(|color| color.is_none()));
+    }
+
+    #[test]
+    fn manual_test_refresh_in_slices() {
+        let mut grid = Grid::new(4, 4);
+        for space in grid.spaces.iter_mut() {
+            *space = Space::Obstacle;
+        }
+        let mut minimap = Minimap::new(4, 4);
+        minimap.refresh(&grid, 4);
+        assert_eq!(Space::Obstacle, minimap.pixels[0]);
+        assert_eq!(Space::Empty, minimap.pixels[4]);
+        minimap.refresh(&grid, 8);
+        assert_eq!(Space::Empty, minimap.pixels[12]);
+        assert!(minimap.is_stale());
+        minimap.refresh(&grid, 4);
+        assert!(minimap.pixels.iter().all(|&space| space == Space::Obstacle));
+        assert!(!minimap.is_stale());
+
+        // Up to date until told the grid changed, and then only in the rows
+        // it changed in.
+        grid.spaces[0] = Space::Empty;
+        grid.spaces[12] = Space::Empty;
+        minimap.refresh(&grid, 4);
+        assert_ne!(minimap.pixel(&grid, 0, 0), minimap.pixels[0]);
+        minimap.mark_changed(Point::new(0, 0));
+        minimap.refresh(&grid, usize::MAX);
+        assert_eq!(minimap.pixel(&grid, 0, 0), minimap.pixels[0]);
+        assert_ne!(minimap.pixel(&grid, 0, 3), minimap.pixels[12]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::grid::Grid;
+use crate::maze::{Progress, Solution, SolverKind, Stats};
+use crate::point::Point;
+
+/// One solver's copy of the maze in a race.
+pub struct Lane {
+    pub solver: SolverKind,
+    pub grid: Arc<Mutex<Grid>>,
+    pub progress: Arc<Progress>,
+}
+
+/// Several solvers working at once, each on its own copy of the same maze.
+pub struct Race {
+    pub lanes: Vec<Lane>,
+}
+
+impl Race {
+    pub fn new(grid: &Grid, solvers: &[SolverKind]) -> Self {
+        let lanes = solvers
+            .iter()
+            .map(|&solver| Lane {
+                solver,
+                grid: Arc::new(Mutex::new(grid.clone())),
+                progress: Arc::new(Progress::default()),
+            })
+            .collect();
+        Self { lanes }
+    }
+
+    /// Starts every lane's solver from `start` on its own thread, sleeping
+    /// `delay` between steps.
+    pub fn start(&self, start: Point, delay: Duration) -> Vec<JoinHandle<Solution>> {
+        self.lanes
+            .iter()
+            .map(|lane| {
+                let (solver, grid, progress) =
+                    (lane.solver, lane.grid.clone(), lane.progress.clone());
+                thread::spawn(move || solver.solve(start, grid, delay, &progress))
+            })
+            .collect()
+    }
+
+    /// Runs every lane to the end as fast as possible.
+    pub fn run(&self, start: Point) -> Vec<Solution> {
+        self.start(start, Duration::ZERO)
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    }
+
+    pub fn stats(&self) -> Vec<Stats> {
+        self.lanes
+            .iter()
+            .map(|lane| lane.progress.stats())
+            .collect()
+    }
+
+    /// Names the winners in `standings`, e.g.
+    /// `fewest expanded: a-star  shortest path: bfs, a-star`.
+    pub fn describe(&self, standings: &Standings) -> String {
+        if standings.shortest_path.is_empty() {
+            return "no path".to_string();
+        }
+        let names = |lanes: &[usize]| {
+            let names: Vec<&str> = lanes.iter().map(|&i| self.lanes[i].solver.name()).collect();
+            names.join(", ")
+        };
+        format!(
+            "fewest expanded: {}  shortest path: {}",
+            names(&standings.fewest_expanded),
+            names(&standings.shortest_path)
+        )
+    }
+}
+
+/// The lanes that did best, by index, with ties sharing a place. Only lanes
+/// that found a path count.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Standings {
+    pub fewest_expanded: Vec<usize>,
+    pub shortest_path: Vec<usize>,
+}
+
+impl Standings {
+    pub fn from_stats(stats: &[Stats]) -> Self {
+        let best = |key: fn(&Stats) -> usize| {
+            let solved = || stats.iter().enumerate().filter(|(_, s)| s.path_len > 0);
+            let Some(min) = solved().map(|(_, s)| key(s)).min() else {
+                return vec![];
+            };
+            solved()
+                .filter(|(_, s)| key(s) == min)
+                .map(|(i, _)| i)
+                .collect()
+        };
+        Self {
+            fewest_expanded: best(|stats| stats.expanded),
+            shortest_path: best(|stats| stats.path_len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod race_tests {
+    use super::{Race, Standings};
+    use crate::{
+        grid::Grid,
+        maze::{GeneratorKind, SolverKind, Stats},
+    };
+
+    #[test]
+    fn manual_test_standings() {
+        let stats = |expanded, path_len| Stats {
+            expanded,
+            path_len,
+            ..Stats::default()
+        };
+        let standings =
+            Standings::from_stats(&[stats(40, 12), stats(9, 20), stats(3, 0), stats(25, 12)]);
+        assert_eq!(vec![1], standings.fewest_expanded);
+        assert_eq!(vec![0, 3], standings.shortest_path);
+
+        assert_eq!(Standings::default(), Standings::from_stats(&[stats(5, 0)]));
+    }
+
+    #[test]
+    fn manual_test_race_lanes_are_independent() {
+        let maze = GeneratorKind::Random.build(Grid::new(16, 10), 3).unwrap();
+        let grid = maze.grid.lock().unwrap().clone();
+        let race = Race::new(&grid, &SolverKind::ALL);
+        let solutions = race.run(maze.start);
+
+        let stats = race.stats();
+        for (solution, stats) in solutions.iter().zip(&stats) {
+            assert_eq!(solution.path.len(), stats.path_len);
+        }
+        // The maze handed in is left alone.
+        assert_eq!(grid.to_string(), maze.grid.lock().unwrap().to_string());
+        let standings = Standings::from_stats(&stats);
+        assert!(standings.shortest_path.contains(&0));
+        assert!(race.describe(&standings).starts_with("fewest expanded: "));
+    }
+}
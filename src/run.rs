@@ -0,0 +1,72 @@
+use crate::maze::Solution;
+use crate::point::Point;
+
+/// Everything needed to describe a single generate-and-solve run, in a shape that
+/// can be handed to other tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunResult {
+    pub generator: String,
+    pub seed: u64,
+    pub solver: String,
+    pub visited: Vec<Point>,
+    pub path: Vec<Point>,
+}
+
+impl RunResult {
+    pub fn new(
+        generator: impl Into<String>,
+        seed: u64,
+        solver: impl Into<String>,
+        solution: Solution,
+    ) -> Self {
+        Self {
+            generator: generator.into(),
+            seed,
+            solver: solver.into(),
+            visited: solution.visited,
+            path: solution.path,
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        !self.path.is_empty()
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod run_result_tests {
+    use super::RunResult;
+    use crate::{grid::Grid, maze::Solution, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_json_round_trip() {
+        let solution = Solution {
+            visited: vec![Point::new(1, 0), Point::new(2, 0)],
+            path: vec![Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)],
+        };
+        let expected = RunResult::new("random", 42, "bfs", solution);
+
+        let json = expected.to_json().unwrap();
+        let actual: RunResult = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn manual_test_grid_json() {
+        let mut grid = Grid::new(2, 1);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Space::Start(Point::new(0, 0));
+        *grid.get_mut(Point::new(1, 0)).unwrap() = Space::Obstacle;
+
+        let actual = serde_json::to_string(&grid).unwrap();
+        let expected = r#"{"spaces":[{"Start":{"x":0,"y":0}},"Obstacle"],"width":2,"height":1}"#;
+
+        assert_eq!(expected, actual);
+    }
+}
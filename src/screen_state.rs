@@ -1,12 +1,16 @@
-use std::io::{self, stdout};
+use std::io::{self, stdout, Write};
 
-use crossterm::{terminal::{self, EnterAlternateScreen, LeaveAlternateScreen}, cursor::{Hide, Show}};
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{DisableMouseCapture, EnableMouseCapture},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
 
 pub struct ScreenState;
 
 impl ScreenState {
     pub fn enable() -> io::Result<Self> {
-        crossterm::execute!(stdout(), EnterAlternateScreen, Hide)?;
+        crossterm::execute!(stdout(), EnterAlternateScreen, Hide, EnableMouseCapture)?;
         terminal::enable_raw_mode()?;
 
         let default_hook = std::panic::take_hook();
@@ -21,10 +25,40 @@ impl ScreenState {
         let _ = terminal::disable_raw_mode().map_err(|err| {
             eprintln!("ERROR: disable_raw_mode: {err}");
         });
-        let _ = crossterm::execute!(stdout(), LeaveAlternateScreen, Show).map_err(|err| {
-            eprintln!("ERROR: LeaveAlternateScreen: {err}");
-        });
+        let _ = crossterm::execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen, Show)
+            .map_err(|err| {
+                eprintln!("ERROR: LeaveAlternateScreen: {err}");
+            });
+    }
+}
+
+/// Puts `text` on the system clipboard by writing the OSC 52 escape sequence
+/// to `out`, the terminal. It works over ssh and in most modern terminal
+/// emulators.
+pub fn copy_to_clipboard(out: &mut impl Write, text: &str) -> io::Result<()> {
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
     }
+    out
 }
 
 impl Drop for ScreenState {
@@ -32,3 +66,16 @@ impl Drop for ScreenState {
         Self::disable();
     }
 }
+
+#[cfg(test)]
+mod screen_state_tests {
+    use super::base64;
+
+    #[test]
+    fn manual_test_base64() {
+        assert_eq!("", base64(b""));
+        assert_eq!("NA==", base64(b"4"));
+        assert_eq!("NDI=", base64(b"42"));
+        assert_eq!("MTIzNDU2", base64(b"123456"));
+    }
+}
@@ -1,10 +1,15 @@
 use crate::point::Point;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Space {
     Obstacle,
     Empty,
-    Visited,
+    /// Found by a solver but not expanded yet, with the distance from the start
+    /// in steps.
+    Frontier(u32),
+    /// Expanded by a solver, with the distance from the start in steps.
+    Visited(u32),
     Path,
     Start(Point),
     End(Point),
@@ -14,14 +19,49 @@ impl Space {
     pub fn is_pathable(&self) -> bool {
         matches!(self, Space::End(_) | Space::Empty)
     }
-}
 
+    /// Distance from the start for frontier and visited spaces.
+    pub fn distance(&self) -> Option<u32> {
+        match self {
+            Space::Frontier(distance) | Space::Visited(distance) => Some(*distance),
+            _ => None,
+        }
+    }
 
+    /// Character used for this space in the plain-text maze format.
+    pub fn to_char(&self) -> char {
+        match self {
+            Space::Obstacle => '#',
+            Space::Empty => '.',
+            Space::Frontier(_) => '+',
+            Space::Visited(_) => 'o',
+            Space::Path => '*',
+            Space::Start(_) => 'S',
+            Space::End(_) => 'E',
+        }
+    }
+
+    /// Inverse of [`Space::to_char`]. `point` is where the character was read
+    /// from, needed for `Start` and `End`.
+    pub fn from_char(ch: char, point: Point) -> Option<Self> {
+        match ch {
+            '#' => Some(Space::Obstacle),
+            '.' => Some(Space::Empty),
+            '+' => Some(Space::Frontier(0)),
+            'o' => Some(Space::Visited(0)),
+            '*' => Some(Space::Path),
+            'S' => Some(Space::Start(point)),
+            'E' => Some(Space::End(point)),
+            _ => None,
+        }
+    }
+}
 
 // Assignment4_Tests Space
 #[cfg(test)]
 mod space_tests {
     use super::Space;
+    use crate::point::Point;
 
     #[test]
     fn manual_test_is_pathable() {
@@ -30,4 +70,22 @@ mod space_tests {
         let space = Space::Empty;
         assert!(space.is_pathable());
     }
+
+    #[test]
+    fn manual_test_char_round_trip() {
+        let point = Point::new(3, 4);
+        let spaces = [
+            Space::Obstacle,
+            Space::Empty,
+            Space::Frontier(0),
+            Space::Visited(0),
+            Space::Path,
+            Space::Start(point),
+            Space::End(point),
+        ];
+        for space in spaces {
+            assert_eq!(Some(space), Space::from_char(space.to_char(), point));
+        }
+        assert_eq!(None, Space::from_char('?', point));
+    }
 }
@@ -0,0 +1,302 @@
+use std::fmt::{self, Write};
+
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::space::Space;
+use crate::wall_maze::WallMaze;
+
+/// A maze as an SVG document, for printing. Drawing is in maze units of one
+/// per cell, scaled up when shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Svg {
+    width: f64,
+    height: f64,
+    /// Room around the maze so wall strokes on its edge aren't cut off.
+    margin: f64,
+    /// Pixels per cell when shown at its natural size.
+    scale: usize,
+    body: String,
+}
+
+impl Svg {
+    const BACKGROUND: &'static str = "#ffffff";
+    const OBSTACLE: &'static str = "#1f2933";
+    const FRONTIER: &'static str = "#fce8b2";
+    const VISITED: &'static str = "#cfe3f7";
+    const PATH: &'static str = "#d1453b";
+    const START: &'static str = "#2f9e44";
+    const END: &'static str = "#1971c2";
+    const PATH_WIDTH: f64 = 0.3;
+    const WALL_WIDTH: f64 = 0.12;
+
+    /// Draws `grid` with its obstacles as merged rectangles and `path`, the
+    /// solution from start to end, as one line through the cells' centres.
+    /// With `shade_visited`, cells the solver visited are shaded, and those
+    /// still on its frontier in another shade.
+    pub fn from_grid(grid: &Grid, path: &[Point], shade_visited: bool, scale: usize) -> Self {
+        let mut svg = Self::new(grid.width as f64, grid.height as f64, 0.0, scale);
+        let space = |x, y| grid.spaces[Point::new(x, y).index(grid.width)];
+        if shade_visited {
+            let visited = |x, y| matches!(space(x, y), Space::Visited(_));
+            svg.rects(Self::VISITED, merge_rects(grid.width, grid.height, visited));
+            let frontier = |x, y| matches!(space(x, y), Space::Frontier(_));
+            svg.rects(
+                Self::FRONTIER,
+                merge_rects(grid.width, grid.height, frontier),
+            );
+        }
+        let obstacle = |x, y| matches!(space(x, y), Space::Obstacle);
+        svg.rects(
+            Self::OBSTACLE,
+            merge_rects(grid.width, grid.height, obstacle),
+        );
+        if let Some(start) = grid.start() {
+            svg.rects(Self::START, vec![(start.x, start.y, 1, 1)]);
+        }
+        if let Some(end) = grid.end() {
+            svg.rects(Self::END, vec![(end.x, end.y, 1, 1)]);
+        }
+        svg.path(path.iter().map(|point| (point.x as f64, point.y as f64)));
+        svg
+    }
+
+    /// Draws `maze` with its walls as lines, merged where they run on, and
+    /// `path` as one line through the centres of its cells, from the top-left
+    /// cell to the bottom-right one like [`WallMaze::to_grid`]. The cells in
+    /// `visited` are shaded.
+    pub fn from_wall_maze(
+        maze: &WallMaze,
+        path: &[Point],
+        visited: &[Point],
+        scale: usize,
+    ) -> Self {
+        let mut svg = Self::new(
+            maze.width as f64,
+            maze.height as f64,
+            Self::WALL_WIDTH,
+            scale,
+        );
+        let mut shaded = vec![false; maze.walls.len()];
+        for point in visited {
+            if point.in_bound(maze.width, maze.height) {
+                shaded[point.index(maze.width)] = true;
+            }
+        }
+        let shaded = |x, y| shaded[Point::new(x, y).index(maze.width)];
+        svg.rects(Self::VISITED, merge_rects(maze.width, maze.height, shaded));
+        if maze.width > 0 && maze.height > 0 {
+            svg.rects(Self::START, vec![(0, 0, 1, 1)]);
+            svg.rects(Self::END, vec![(maze.width - 1, maze.height - 1, 1, 1)]);
+        }
+
+        // Walls along each lattice row, then each lattice column.
+        let mut walls = String::new();
+        for y in 0..=maze.height {
+            for (start, end) in runs(maze.width, |x| maze.horizontal_wall(x, y)) {
+                let _ = write!(walls, "M{start} {y}H{end}");
+            }
+        }
+        for x in 0..=maze.width {
+            for (start, end) in runs(maze.height, |y| maze.vertical_wall(x, y)) {
+                let _ = write!(walls, "M{x} {start}V{end}");
+            }
+        }
+        if !walls.is_empty() {
+            let _ = writeln!(
+                svg.body,
+                r#"<path d="{walls}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="square"/>"#,
+                Self::OBSTACLE,
+                Self::WALL_WIDTH
+            );
+        }
+        svg.path(path.iter().map(|point| (point.x as f64, point.y as f64)));
+        svg
+    }
+
+    fn new(width: f64, height: f64, margin: f64, scale: usize) -> Self {
+        let mut svg = Self {
+            width,
+            height,
+            margin,
+            scale: scale.max(1),
+            body: String::new(),
+        };
+        svg.rects(
+            Self::BACKGROUND,
+            vec![(0, 0, width as usize, height as usize)],
+        );
+        svg
+    }
+
+    /// Adds the rectangles `(x, y, width, height)` filled with `fill`.
+    fn rects(&mut self, fill: &str, rects: Vec<(usize, usize, usize, usize)>) {
+        if rects.is_empty() {
+            return;
+        }
+        let _ = writeln!(
+            self.body,
+            r#"<g fill="{fill}" shape-rendering="crispEdges">"#
+        );
+        for (x, y, width, height) in rects {
+            let _ = writeln!(
+                self.body,
+                r#"<rect x="{x}" y="{y}" width="{width}" height="{height}"/>"#
+            );
+        }
+        self.body.push_str("</g>\n");
+    }
+
+    /// Adds a line through the centres of the cells at `cells`.
+    fn path(&mut self, cells: impl Iterator<Item = (f64, f64)>) {
+        let points: Vec<String> = cells
+            .map(|(x, y)| format!("{},{}", x + 0.5, y + 0.5))
+            .collect();
+        if points.is_empty() {
+            return;
+        }
+        let _ = writeln!(
+            self.body,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+            points.join(" "),
+            Self::PATH,
+            Self::PATH_WIDTH
+        );
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (width, height) = (
+            self.width + self.margin * 2.0,
+            self.height + self.margin * 2.0,
+        );
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {width} {height}">"#,
+            (width * self.scale as f64).round(),
+            (height * self.scale as f64).round(),
+            // Subtracted so no margin reads `0` rather than `-0`.
+            0.0 - self.margin,
+            0.0 - self.margin,
+        )?;
+        f.write_str(&self.body)?;
+        writeln!(f, "</svg>")
+    }
+}
+
+/// The runs of `0..len` where `filled` holds, as `(start, end)` with `end`
+/// exclusive.
+fn runs(len: usize, filled: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+    let mut runs = vec![];
+    let mut start = None;
+    for i in 0..=len {
+        match (start, i < len && filled(i)) {
+            (None, true) => start = Some(i),
+            (Some(run), false) => {
+                runs.push((run, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// Covers the cells of a `width` by `height` area where `filled` holds with
+/// rectangles `(x, y, width, height)`: runs along each row, each grown down
+/// over the rows below for as long as they are filled across the same span.
+fn merge_rects(
+    width: usize,
+    height: usize,
+    filled: impl Fn(usize, usize) -> bool,
+) -> Vec<(usize, usize, usize, usize)> {
+    let mut covered = vec![false; width * height];
+    let free = |covered: &[bool], x, y| filled(x, y) && !covered[y * width + x];
+    let mut rects = vec![];
+    for y in 0..height {
+        let mut x = 0;
+        while x < width {
+            if !free(&covered, x, y) {
+                x += 1;
+                continue;
+            }
+            let mut end = x + 1;
+            while end < width && free(&covered, end, y) {
+                end += 1;
+            }
+            let mut bottom = y + 1;
+            while bottom < height && (x..end).all(|x| free(&covered, x, bottom)) {
+                bottom += 1;
+            }
+            for row in y..bottom {
+                covered[row * width + x..row * width + end].fill(true);
+            }
+            rects.push((x, y, end - x, bottom - y));
+            x = end;
+        }
+    }
+    rects
+}
+
+#[cfg(test)]
+mod svg_tests {
+    use super::{merge_rects, runs, Svg};
+    use crate::{grid::Grid, point::Point, space::Space, wall_maze::WallMaze};
+
+    #[test]
+    fn manual_test_merge_rects() {
+        // # # .
+        // # # #
+        // . # #
+        let cells = ["##.", "###", ".##"];
+        let filled = |x: usize, y: usize| cells[y].as_bytes()[x] == b'#';
+        assert_eq!(
+            vec![(0, 0, 2, 2), (2, 1, 1, 2), (1, 2, 1, 1)],
+            merge_rects(3, 3, filled)
+        );
+        assert_eq!(vec![(1, 3), (4, 5)], runs(5, |i| i != 0 && i != 3));
+    }
+
+    #[test]
+    fn manual_test_grid_svg() {
+        let mut grid = Grid::new(3, 2);
+        let (start, end) = (Point::new(0, 0), Point::new(2, 0));
+        *grid.get_mut(start).unwrap() = Space::Start(start);
+        *grid.get_mut(end).unwrap() = Space::End(end);
+        *grid.get_mut(Point::new(1, 0)).unwrap() = Space::Obstacle;
+        *grid.get_mut(Point::new(0, 1)).unwrap() = Space::Path;
+        *grid.get_mut(Point::new(1, 1)).unwrap() = Space::Path;
+        *grid.get_mut(Point::new(2, 1)).unwrap() = Space::Visited(3);
+        let path = [
+            start,
+            Point::new(0, 1),
+            Point::new(1, 1),
+            Point::new(2, 1),
+            end,
+        ];
+
+        let svg = Svg::from_grid(&grid, &path, false, 10).to_string();
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20" viewBox="0 0 3 2">"#
+        ));
+        assert!(svg.contains(r#"<rect x="1" y="0" width="1" height="1"/>"#));
+        assert!(svg.contains(r#"points="0.5,0.5 0.5,1.5 1.5,1.5 2.5,1.5 2.5,0.5""#));
+        assert!(!svg.contains(Svg::VISITED));
+        assert!(Svg::from_grid(&grid, &path, true, 10)
+            .to_string()
+            .contains(Svg::VISITED));
+    }
+
+    #[test]
+    fn manual_test_wall_maze_svg() {
+        // Two cells side by side with the wall between them knocked down.
+        let mut maze = WallMaze::new(2, 1);
+        maze.carve(0);
+        let path = [Point::new(0, 0), Point::new(1, 0)];
+
+        let svg = Svg::from_wall_maze(&maze, &path, &[], 10).to_string();
+        assert!(svg.contains(r#"d="M0 0H2M0 1H2M0 0V1M2 0V1""#));
+        assert!(svg.contains(r#"points="0.5,0.5 1.5,0.5""#));
+        assert!(svg.contains(r#"viewBox="-0.12 -0.12 2.24 1.24""#));
+    }
+}
@@ -0,0 +1,439 @@
+use std::{fmt, io, path::Path};
+
+use crossterm::style::{Color, Colors};
+
+use crate::buffer::Cell;
+use crate::space::Space;
+
+/// The built-in themes, selectable by name.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ThemeKind {
+    #[default]
+    Default,
+    HighContrast,
+    Solarized,
+    Monochrome,
+    /// Okabe-Ito palette, distinguishable with the common colour-vision
+    /// deficiencies.
+    ColourBlind,
+    /// Plain glyphs and no colour, for terminals that can't show any.
+    Ascii,
+}
+
+impl ThemeKind {
+    pub const ALL: [ThemeKind; 6] = [
+        ThemeKind::Default,
+        ThemeKind::HighContrast,
+        ThemeKind::Solarized,
+        ThemeKind::Monochrome,
+        ThemeKind::ColourBlind,
+        ThemeKind::Ascii,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeKind::Default => "default",
+            ThemeKind::HighContrast => "high-contrast",
+            ThemeKind::Solarized => "solarized",
+            ThemeKind::Monochrome => "monochrome",
+            ThemeKind::ColourBlind => "colour-blind",
+            ThemeKind::Ascii => "ascii",
+        }
+    }
+
+    pub fn theme(&self) -> Theme {
+        let rgb = |r, g, b| Some(Color::Rgb { r, g, b });
+        match self {
+            ThemeKind::Default => Theme {
+                name: self.name().to_string(),
+                ..DEFAULT_THEME
+            },
+            ThemeKind::HighContrast => Theme {
+                name: self.name().to_string(),
+                obstacle: cell(' ', None, Some(Color::White)),
+                empty: cell(' ', None, None),
+                frontier: cell('+', Some(Color::Cyan), None),
+                visited: cell('o', Some(Color::Yellow), None),
+                path: cell(' ', None, Some(Color::Magenta)),
+                start: cell('S', Some(Color::Black), Some(Color::Green)),
+                end: cell('E', Some(Color::Black), Some(Color::Red)),
+            },
+            ThemeKind::Solarized => Theme {
+                name: self.name().to_string(),
+                obstacle: cell(' ', None, rgb(88, 110, 117)),
+                empty: cell(' ', None, rgb(0, 43, 54)),
+                frontier: cell('+', rgb(42, 161, 152), rgb(7, 54, 66)),
+                visited: cell('o', rgb(38, 139, 210), rgb(0, 43, 54)),
+                path: cell(' ', None, rgb(181, 137, 0)),
+                start: cell(' ', None, rgb(133, 153, 0)),
+                end: cell(' ', None, rgb(220, 50, 47)),
+            },
+            ThemeKind::Monochrome => Theme {
+                name: self.name().to_string(),
+                obstacle: cell(' ', None, Some(Color::Grey)),
+                empty: cell(' ', None, None),
+                frontier: cell('+', Some(Color::White), None),
+                visited: cell('·', Some(Color::Grey), None),
+                path: cell(' ', None, Some(Color::White)),
+                start: cell('S', Some(Color::White), Some(Color::DarkGrey)),
+                end: cell('E', Some(Color::White), Some(Color::DarkGrey)),
+            },
+            ThemeKind::ColourBlind => Theme {
+                name: self.name().to_string(),
+                obstacle: cell(' ', None, rgb(110, 110, 110)),
+                empty: cell(' ', None, None),
+                frontier: cell('+', rgb(240, 228, 66), None),
+                visited: cell('o', rgb(86, 180, 233), None),
+                path: cell(' ', None, rgb(230, 159, 0)),
+                start: cell(' ', None, rgb(0, 114, 178)),
+                end: cell(' ', None, rgb(213, 94, 0)),
+            },
+            ThemeKind::Ascii => Theme {
+                name: self.name().to_string(),
+                obstacle: cell('#', None, None),
+                empty: cell(' ', None, None),
+                frontier: cell('+', None, None),
+                visited: cell('.', None, None),
+                path: cell('*', None, None),
+                start: cell('S', None, None),
+                end: cell('E', None, None),
+            },
+        }
+    }
+}
+
+/// The default theme, nameless so it can be a constant. [`Cell::from`] reads
+/// it for every space it converts, so it mustn't need building each time.
+pub(crate) const DEFAULT_THEME: Theme = Theme {
+    name: String::new(),
+    obstacle: cell(' ', None, Some(Color::DarkRed)),
+    empty: cell(' ', None, None),
+    frontier: cell('o', Some(Color::Yellow), None),
+    visited: cell('O', Some(Color::White), None),
+    path: cell(' ', None, Some(Color::AnsiValue(7))),
+    start: cell(' ', None, Some(Color::DarkBlue)),
+    end: cell(' ', None, Some(Color::DarkGreen)),
+};
+
+const fn cell(ch: char, foreground: Option<Color>, background: Option<Color>) -> Cell {
+    Cell {
+        ch,
+        colors: Colors {
+            foreground,
+            background,
+        },
+    }
+}
+
+/// Glyph and colours for every kind of [`Space`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub name: String,
+    pub obstacle: Cell,
+    pub empty: Cell,
+    pub frontier: Cell,
+    pub visited: Cell,
+    pub path: Cell,
+    pub start: Cell,
+    pub end: Cell,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        ThemeKind::Default.theme()
+    }
+}
+
+impl Theme {
+    pub fn cell(&self, space: Space) -> Cell {
+        match space {
+            Space::Obstacle => self.obstacle,
+            Space::Empty => self.empty,
+            Space::Frontier(_) => self.frontier,
+            Space::Visited(_) => self.visited,
+            Space::Path => self.path,
+            Space::Start(_) => self.start,
+            Space::End(_) => self.end,
+        }
+    }
+
+    /// This theme for a terminal without colour, where only glyphs tell spaces
+    /// apart. Spaces that share a glyph with another take their
+    /// [`ThemeKind::Ascii`] glyph instead.
+    pub fn without_color(&self) -> Theme {
+        let mut ascii = ThemeKind::Ascii.theme();
+        let ascii: Vec<char> = ascii.cells_mut().iter().map(|cell| cell.ch).collect();
+        let mut theme = self.clone();
+        let mut cells = theme.cells_mut();
+        for cell in cells.iter_mut() {
+            cell.colors = Colors {
+                foreground: None,
+                background: None,
+            };
+        }
+        // The Ascii glyphs are all different, so this ends once every shared
+        // glyph has been swapped for one.
+        while let Some(i) = (0..cells.len()).find(|&i| {
+            cells[i].ch != ascii[i]
+                && cells.iter().filter(|cell| cell.ch == cells[i].ch).count() > 1
+        }) {
+            cells[i].ch = ascii[i];
+        }
+        theme
+    }
+
+    fn cells_mut(&mut self) -> [&mut Cell; 7] {
+        [
+            &mut self.obstacle,
+            &mut self.empty,
+            &mut self.frontier,
+            &mut self.visited,
+            &mut self.path,
+            &mut self.start,
+            &mut self.end,
+        ]
+    }
+
+    fn cell_mut(&mut self, space: &str) -> Option<&mut Cell> {
+        match space {
+            "obstacle" => Some(&mut self.obstacle),
+            "empty" => Some(&mut self.empty),
+            "frontier" => Some(&mut self.frontier),
+            "visited" => Some(&mut self.visited),
+            "path" => Some(&mut self.path),
+            "start" => Some(&mut self.start),
+            "end" => Some(&mut self.end),
+            _ => None,
+        }
+    }
+
+    /// Reads a theme file. Each non-blank line that isn't a `#` comment sets one
+    /// space, and spaces left out keep the default theme's look:
+    ///
+    /// ```text
+    /// # space   glyph  foreground  background
+    /// obstacle  ' '    none        dark_red
+    /// visited   'o'    #268bd2     none
+    /// path      ' '    none        ansi:7
+    /// ```
+    ///
+    /// Colours are `none`, a crossterm colour name such as `dark_blue`,
+    /// `#rrggbb`, or `ansi:<0-255>`.
+    pub fn parse(name: &str, text: &str) -> Result<Self, ParseThemeError> {
+        let mut theme = Theme {
+            name: name.to_string(),
+            ..Theme::default()
+        };
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (space, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let (ch, rest) = parse_glyph(rest.trim_start()).ok_or(ParseThemeError {
+                line: line_no,
+                kind: ThemeErrorKind::BadGlyph,
+            })?;
+            let mut colors = rest.split_whitespace().map(|value| {
+                parse_color(value).ok_or_else(|| ParseThemeError {
+                    line: line_no,
+                    kind: ThemeErrorKind::BadColor(value.to_string()),
+                })
+            });
+            let missing = ParseThemeError {
+                line: line_no,
+                kind: ThemeErrorKind::MissingColor,
+            };
+            let foreground = colors.next().ok_or(missing.clone())??;
+            let background = colors.next().ok_or(missing)??;
+
+            let target = theme.cell_mut(space).ok_or_else(|| ParseThemeError {
+                line: line_no,
+                kind: ThemeErrorKind::UnknownSpace(space.to_string()),
+            })?;
+            *target = cell(ch, foreground, background);
+        }
+        Ok(theme)
+    }
+
+    /// Loads a theme file, named after the file stem. See [`Theme::parse`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let name = path
+            .file_stem()
+            .map_or("custom".into(), |stem| stem.to_string_lossy());
+        Theme::parse(&name, &std::fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// A glyph is either a bare character or one wrapped in single quotes, which is
+/// how a space is written.
+fn parse_glyph(s: &str) -> Option<(char, &str)> {
+    let mut chars = s.chars();
+    match (chars.next()?, chars.clone().next()) {
+        ('\'', Some(ch)) => {
+            chars.next();
+            (chars.next()? == '\'').then_some((ch, chars.as_str()))
+        }
+        (ch, next) if next.is_none_or(char::is_whitespace) => Some((ch, chars.as_str())),
+        _ => None,
+    }
+}
+
+/// `Ok(None)` is the terminal's own colour.
+fn parse_color(s: &str) -> Option<Option<Color>> {
+    if s == "none" {
+        return Some(None);
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        let value = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)?;
+        return Some(Some(Color::Rgb {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        }));
+    }
+    if let Some(ansi) = s.strip_prefix("ansi:") {
+        return ansi.parse().ok().map(|value| Some(Color::AnsiValue(value)));
+    }
+    Color::try_from(s).ok().map(Some)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseThemeError {
+    pub line: usize,
+    pub kind: ThemeErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeErrorKind {
+    UnknownSpace(String),
+    BadGlyph,
+    BadColor(String),
+    MissingColor,
+}
+
+impl fmt::Display for ParseThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ThemeErrorKind::UnknownSpace(space) => write!(f, "unknown space {space:?}"),
+            ThemeErrorKind::BadGlyph => write!(f, "expected a glyph such as 'o' or ' '"),
+            ThemeErrorKind::BadColor(color) => write!(f, "unknown colour {color:?}"),
+            ThemeErrorKind::MissingColor => write!(f, "expected a foreground and a background"),
+        }
+    }
+}
+
+impl std::error::Error for ParseThemeError {}
+
+#[cfg(test)]
+mod theme_tests {
+    use crossterm::style::Color;
+
+    use super::{cell, ParseThemeError, Theme, ThemeErrorKind, ThemeKind};
+    use crate::{buffer::Cell, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_builtin_themes_distinguish_spaces() {
+        let spaces = [
+            Space::Obstacle,
+            Space::Empty,
+            Space::Frontier(0),
+            Space::Visited(0),
+            Space::Path,
+            Space::Start(Point::new(0, 0)),
+            Space::End(Point::new(0, 0)),
+        ];
+        for kind in ThemeKind::ALL {
+            let theme = kind.theme();
+            for (i, a) in spaces.iter().enumerate() {
+                for b in &spaces[i + 1..] {
+                    assert_ne!(theme.cell(*a), theme.cell(*b), "{}", theme.name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn manual_test_without_color_keeps_spaces_apart() {
+        let theme = Theme::parse("custom", "visited '#' none none").unwrap();
+        let theme = theme.without_color();
+        let glyphs: Vec<char> = [
+            &theme.obstacle,
+            &theme.empty,
+            &theme.frontier,
+            &theme.visited,
+            &theme.path,
+            &theme.start,
+            &theme.end,
+        ]
+        .iter()
+        .map(|cell| cell.ch)
+        .collect();
+        assert_eq!(vec!['#', ' ', 'o', '.', '*', 'S', 'E'], glyphs);
+        assert_eq!(None, theme.path.colors.background);
+    }
+
+    #[test]
+    fn manual_test_default_matches_cell_from_space() {
+        let theme = Theme::default();
+        assert_eq!(Cell::from(Space::Obstacle), theme.cell(Space::Obstacle));
+        assert_eq!(Cell::from(Space::Path), theme.cell(Space::Path));
+    }
+
+    #[test]
+    fn manual_test_parse() {
+        let text = "
+            # a comment
+            obstacle '#' white none
+            visited  ' ' none  #268bd2
+            path     *   none  ansi:7
+        ";
+        let theme = Theme::parse("mine", text).unwrap();
+
+        assert_eq!("mine", theme.name);
+        assert_eq!(cell('#', Some(Color::White), None), theme.obstacle);
+        assert_eq!(
+            cell(
+                ' ',
+                None,
+                Some(Color::Rgb {
+                    r: 38,
+                    g: 139,
+                    b: 210
+                })
+            ),
+            theme.visited
+        );
+        assert_eq!(cell('*', None, Some(Color::AnsiValue(7))), theme.path);
+        assert_eq!(Theme::default().start, theme.start);
+    }
+
+    #[test]
+    fn manual_test_parse_errors() {
+        let error = |line, kind| Err(ParseThemeError { line, kind });
+
+        assert_eq!(
+            error(1, ThemeErrorKind::UnknownSpace("wall".into())),
+            Theme::parse("t", "wall ' ' none none")
+        );
+        assert_eq!(
+            error(2, ThemeErrorKind::BadColor("purple".into())),
+            Theme::parse("t", "\npath ' ' purple none")
+        );
+        assert_eq!(
+            error(1, ThemeErrorKind::MissingColor),
+            Theme::parse("t", "path ' ' none")
+        );
+        assert_eq!(
+            error(1, ThemeErrorKind::BadGlyph),
+            Theme::parse("t", "path ab none none")
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use crate::grid::Grid;
+use crate::maze::Change;
+use crate::point::Point;
+use crate::space::Space;
+
+/// Every change made to a grid, in order, so the grid can be shown as it was
+/// after any of them. Changes keep being recorded while an earlier one is
+/// shown.
+///
+/// A big solve records millions of changes, so each is kept in 16 bytes: see
+/// [`Step`].
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    /// The grid as of `position`.
+    grid: Grid,
+    /// The cell each space was reached from as of `position`, by index.
+    preds: Vec<Option<Point>>,
+    /// The spaces and where they were reached from, by index, as of the last
+    /// change recorded.
+    latest: Vec<Packed>,
+    latest_preds: Vec<u32>,
+    steps: Vec<Step>,
+    /// The steps that gave a cell a new predecessor in place of an older
+    /// one, by step, with the older one. Only A* finds a cell again by a
+    /// shorter way, so this stays short.
+    replaced_preds: Vec<(usize, u32)>,
+    position: usize,
+}
+
+/// A recorded change: the cell it made, the space it set and the space that
+/// was there before, so it can be undone.
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    index: u32,
+    /// The cell `index` was reached from, by index, or [`NO_PRED`].
+    from: u32,
+    space: Packed,
+    old: Packed,
+}
+
+/// [`Step::from`] for changes that don't say where a cell was reached from.
+const NO_PRED: u32 = u32::MAX;
+
+/// A [`Space`] in 32 bits: which kind it is in the top three and its distance
+/// in the rest, so distances of 2^29 and more are cut short. Start and end
+/// spaces hold the point of their own cell, which is left out and put back
+/// from the cell they are in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Packed(u32);
+
+impl Packed {
+    const SHIFT: u32 = 29;
+    const DISTANCE: u32 = (1 << Self::SHIFT) - 1;
+
+    fn new(space: Space) -> Self {
+        let (kind, distance) = match space {
+            Space::Obstacle => (0, 0),
+            Space::Empty => (1, 0),
+            Space::Frontier(distance) => (2, distance),
+            Space::Visited(distance) => (3, distance),
+            Space::Path => (4, 0),
+            Space::Start(_) => (5, 0),
+            Space::End(_) => (6, 0),
+        };
+        Self(kind << Self::SHIFT | distance.min(Self::DISTANCE))
+    }
+
+    /// The space, in the cell at `point`.
+    fn unpack(self, point: Point) -> Space {
+        let distance = self.0 & Self::DISTANCE;
+        match self.0 >> Self::SHIFT {
+            0 => Space::Obstacle,
+            1 => Space::Empty,
+            2 => Space::Frontier(distance),
+            3 => Space::Visited(distance),
+            4 => Space::Path,
+            5 => Space::Start(point),
+            _ => Space::End(point),
+        }
+    }
+}
+
+impl Timeline {
+    /// Starts a timeline with no changes at `grid`.
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            preds: vec![None; grid.spaces.len()],
+            latest: grid
+                .spaces
+                .iter()
+                .map(|&space| Packed::new(space))
+                .collect(),
+            latest_preds: vec![NO_PRED; grid.spaces.len()],
+            grid,
+            steps: vec![],
+            replaced_preds: vec![],
+            position: 0,
+        }
+    }
+
+    /// The grid as of the change shown.
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// The cell each space shown was reached from by the solver, by index,
+    /// for the spaces it has found.
+    pub fn preds(&self) -> &[Option<Point>] {
+        &self.preds
+    }
+
+    /// How many changes the grid shows.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Whether the grid shows every change recorded.
+    pub fn is_live(&self) -> bool {
+        self.position == self.steps.len()
+    }
+
+    /// Where the last change shown was made.
+    pub fn current(&self) -> Option<Point> {
+        let step = self.steps.get(self.position.checked_sub(1)?)?;
+        Some(self.point(step.index))
+    }
+
+    fn point(&self, index: u32) -> Point {
+        Point::from_index(index as usize, self.grid.width)
+    }
+
+    /// Adds `change` to the end of the timeline, and shows it if the grid was
+    /// live. Returns whether it is shown. Changes outside the grid are left
+    /// out.
+    pub fn record(&mut self, change: Change) -> bool {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let index = |point: Point| {
+            point
+                .in_bound(width, height)
+                .then(|| u32::try_from(point.index(width)).ok())
+                .flatten()
+        };
+        let Some(at) = index(change.point) else {
+            return false;
+        };
+        let from = change.from.and_then(index).unwrap_or(NO_PRED);
+        let space = Packed::new(change.space);
+        let old = std::mem::replace(&mut self.latest[at as usize], space);
+        if from != NO_PRED {
+            let pred = std::mem::replace(&mut self.latest_preds[at as usize], from);
+            if pred != NO_PRED {
+                self.replaced_preds.push((self.steps.len(), pred));
+            }
+        }
+        let step = Step {
+            index: at,
+            from,
+            space,
+            old,
+        };
+        self.steps.push(step);
+        if self.position + 1 < self.steps.len() {
+            return false;
+        }
+        self.redo(step);
+        true
+    }
+
+    /// Shows the grid as of the first `position` changes, or all of them if
+    /// there are fewer, adding each point redone or undone to `changed`.
+    pub fn seek(&mut self, position: usize, changed: &mut Vec<Point>) {
+        let position = position.min(self.steps.len());
+        while self.position < position {
+            let step = self.steps[self.position];
+            self.redo(step);
+            changed.push(self.point(step.index));
+        }
+        while self.position > position {
+            self.position -= 1;
+            let step = self.steps[self.position];
+            let (index, point) = (step.index as usize, self.point(step.index));
+            self.grid.spaces[index] = step.old.unpack(point);
+            if step.from != NO_PRED {
+                // Undoing the step that first found the cell leaves it with
+                // no predecessor.
+                let replaced = self
+                    .replaced_preds
+                    .binary_search_by_key(&self.position, |&(step, _)| step);
+                self.preds[index] = replaced.ok().map(|i| self.point(self.replaced_preds[i].1));
+            }
+            changed.push(point);
+        }
+    }
+
+    /// Shows `step`, the one after those shown.
+    fn redo(&mut self, step: Step) {
+        let point = self.point(step.index);
+        self.grid.spaces[step.index as usize] = step.space.unpack(point);
+        if step.from != NO_PRED {
+            self.preds[step.index as usize] = Some(self.point(step.from));
+        }
+        self.position += 1;
+    }
+
+    /// Moves `by` changes forwards, or backwards if it is negative, like
+    /// [`Timeline::seek`].
+    pub fn step(&mut self, by: isize, changed: &mut Vec<Point>) {
+        self.seek(self.position.saturating_add_signed(by), changed);
+    }
+}
+
+#[cfg(test)]
+mod timeline_tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{Packed, Step, Timeline};
+    use crate::{grid::Grid, maze::Change, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_scrub() {
+        let mut timeline = Timeline::new(Grid::new(2, 1));
+        let (left, right) = (Point::new(0, 0), Point::new(1, 0));
+        assert!(timeline.record(Change {
+            point: left,
+            space: Space::Frontier(1),
+            from: Some(right),
+        }));
+        assert!(timeline.record(Change {
+            point: left,
+            space: Space::Path,
+            from: None,
+        }));
+        assert!(!timeline.record(Change {
+            point: Point::new(2, 0),
+            space: Space::Path,
+            from: None,
+        }));
+
+        let mut changed = vec![];
+        timeline.step(-1, &mut changed);
+        assert_eq!(vec![left], changed);
+        assert_eq!(Some(Space::Frontier(1)), timeline.grid().get(left));
+        assert_eq!(Some(left), timeline.current());
+        assert_eq!(&[Some(right), None], timeline.preds());
+
+        // Recorded but not shown until the timeline is back at its end.
+        assert!(!timeline.record(Change {
+            point: right,
+            space: Space::Obstacle,
+            from: None,
+        }));
+        assert_eq!((1, 3), (timeline.position(), timeline.len()));
+        timeline.seek(0, &mut changed);
+        assert_eq!(Grid::new(2, 1).spaces, timeline.grid().spaces);
+        assert_eq!(None, timeline.current());
+        assert_eq!(&[None, None], timeline.preds());
+
+        timeline.step(10, &mut changed);
+        assert!(timeline.is_live());
+        assert_eq!(Some(Space::Path), timeline.grid().get(left));
+        assert_eq!(&[Some(right), None], timeline.preds());
+        assert_eq!(Some(Space::Obstacle), timeline.grid().get(right));
+    }
+
+    #[test]
+    fn manual_test_steps_are_small() {
+        assert_eq!(16, std::mem::size_of::<Step>());
+
+        let point = Point::new(3, 4);
+        let spaces = [
+            Space::Obstacle,
+            Space::Empty,
+            Space::Frontier(7),
+            Space::Visited(Packed::DISTANCE),
+            Space::Path,
+            Space::Start(point),
+            Space::End(point),
+        ];
+        for space in spaces {
+            assert_eq!(space, Packed::new(space).unpack(point));
+        }
+    }
+
+    #[test]
+    fn fuzzy_test_seek_matches_replay() {
+        let mut rng = StdRng::seed_from_u64(48);
+        for _ in 0..20 {
+            let (width, height) = (rng.gen_range(1..8), rng.gen_range(1..8));
+            let changes: Vec<Change> = (0..rng.gen_range(0..60))
+                .map(|_| Change {
+                    point: Point::new(rng.gen_range(0..width), rng.gen_range(0..height)),
+                    space: Space::Visited(rng.gen_range(0..5)),
+                    from: rng.gen_bool(0.5).then(|| Point::new(0, 0)),
+                })
+                .collect();
+            let mut timeline = Timeline::new(Grid::new(width, height));
+            for &change in &changes {
+                timeline.record(change);
+            }
+
+            let mut changed = vec![];
+            for _ in 0..10 {
+                let position = rng.gen_range(0..=changes.len());
+                timeline.seek(position, &mut changed);
+                let mut expected = Grid::new(width, height);
+                let mut preds = vec![None; width * height];
+                for change in &changes[..position] {
+                    *expected.get_mut(change.point).unwrap() = change.space;
+                    if change.from.is_some() {
+                        preds[change.point.index(width)] = change.from;
+                    }
+                }
+                assert_eq!(expected.spaces, timeline.grid().spaces);
+                assert_eq!(preds, timeline.preds());
+            }
+        }
+    }
+}
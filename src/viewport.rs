@@ -0,0 +1,220 @@
+use crate::{grid::Grid, point::Point, space::Space};
+
+/// The part of the screen the grid is drawn in, and which part of the grid it
+/// shows. Positions are in terminal cells of the zoomed grid, so with
+/// [`RenderMode::HalfBlock`] one row of the viewport covers two rows of
+/// `zoom` by `zoom` blocks.
+///
+/// [`RenderMode::HalfBlock`]: crate::buffer::RenderMode::HalfBlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// Size of the window on screen. It sits in the top-left corner.
+    pub width: usize,
+    pub height: usize,
+    /// The position shown in the window's top-left corner. Negative values
+    /// leave a margin before the grid starts.
+    pub x: isize,
+    pub y: isize,
+    /// Grid cells along each side of the block drawn as one cell.
+    pub zoom: usize,
+}
+
+impl Viewport {
+    /// The zoom levels stepped through when zooming in and out.
+    pub const ZOOM_LEVELS: [usize; 6] = [1, 2, 4, 8, 16, 32];
+
+    /// A `width` by `height` window over content `content_width` by
+    /// `content_height` cells, centred on it. Smaller content gets an even
+    /// margin and larger content is cropped to its middle.
+    pub fn centred(
+        width: usize,
+        height: usize,
+        content_width: usize,
+        content_height: usize,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            x: (content_width as isize - width as isize) / 2,
+            y: (content_height as isize - height as isize) / 2,
+            zoom: 1,
+        }
+    }
+
+    /// The content position shown at window position `x`, `y`, or `None` if
+    /// it is outside the window or before the start of the content.
+    pub fn to_grid(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let grid_x = usize::try_from(x as isize + self.x).ok()?;
+        let grid_y = usize::try_from(y as isize + self.y).ok()?;
+        Some((grid_x, grid_y))
+    }
+
+    /// Keeps the window over the content: content that fits is centred, and
+    /// larger content can be scrolled up to its edges but not past them.
+    pub fn clamp(&mut self, content_width: usize, content_height: usize) {
+        fn axis(offset: isize, window: usize, content: usize) -> isize {
+            let spare = content as isize - window as isize;
+            if spare <= 0 {
+                spare / 2
+            } else {
+                offset.clamp(0, spare)
+            }
+        }
+        self.x = axis(self.x, self.width, content_width);
+        self.y = axis(self.y, self.height, content_height);
+    }
+
+    /// Scrolls by `dx`, `dy` cells, staying over the content.
+    pub fn pan(&mut self, dx: isize, dy: isize, content_width: usize, content_height: usize) {
+        self.x += dx;
+        self.y += dy;
+        self.clamp(content_width, content_height);
+    }
+
+    /// Puts content position `x`, `y` in the middle of the window, as far as
+    /// the content's edges allow.
+    pub fn centre_on(&mut self, x: usize, y: usize, content_width: usize, content_height: usize) {
+        self.x = x as isize - self.width as isize / 2;
+        self.y = y as isize - self.height as isize / 2;
+        self.clamp(content_width, content_height);
+    }
+
+    /// Whether content position `x`, `y` is in the middle half of the window,
+    /// away from its edges.
+    pub fn is_near_centre(&self, x: usize, y: usize) -> bool {
+        let near = |pos: usize, offset: isize, window: usize| {
+            let pos = pos as isize - offset;
+            let margin = window as isize / 4;
+            pos >= margin && pos < window as isize - margin
+        };
+        near(x, self.x, self.width) && near(y, self.y, self.height)
+    }
+
+    /// The next zoom level out, or in when `out` is false, if there is one.
+    pub fn step_zoom(&self, out: bool) -> Option<usize> {
+        let levels = Self::ZOOM_LEVELS;
+        let i = levels.iter().position(|&zoom| zoom >= self.zoom)?;
+        match out {
+            true => levels.get(i + 1).copied(),
+            false => i.checked_sub(1).map(|i| levels[i]),
+        }
+    }
+}
+
+/// The space standing for the `zoom` by `zoom` block of `grid` at block `x`,
+/// `y`, or `None` past the edge of the grid. The most telling space in the
+/// block wins, so the start, end, path, frontier and the rest of the search
+/// stay visible when zoomed out. Otherwise the block is an obstacle if at
+/// least half of it is.
+pub fn sample(grid: &Grid, zoom: usize, x: usize, y: usize) -> Option<Space> {
+    let zoom = zoom.max(1);
+    let first = grid.get(Point::new(x * zoom, y * zoom))?;
+    if zoom == 1 {
+        return Some(first);
+    }
+
+    let rank = |space: &Space| match space {
+        Space::Start(_) | Space::End(_) => 4,
+        Space::Path => 3,
+        Space::Frontier(_) => 2,
+        Space::Visited(_) => 1,
+        Space::Obstacle | Space::Empty => 0,
+    };
+    let mut best = Space::Empty;
+    let (mut obstacles, mut cells) = (0, 0);
+    for block_y in y * zoom..((y + 1) * zoom).min(grid.height) {
+        for block_x in x * zoom..((x + 1) * zoom).min(grid.width) {
+            let space = grid.spaces[Point::new(block_x, block_y).index(grid.width)];
+            if rank(&space) > rank(&best) {
+                best = space;
+            }
+            obstacles += matches!(space, Space::Obstacle) as usize;
+            cells += 1;
+        }
+    }
+    if rank(&best) == 0 && obstacles * 2 >= cells {
+        best = Space::Obstacle;
+    }
+    Some(best)
+}
+
+#[cfg(test)]
+mod viewport_tests {
+    use super::{sample, Viewport};
+    use crate::{grid::Grid, point::Point, space::Space};
+
+    #[test]
+    fn manual_test_centred_small_grid() {
+        let viewport = Viewport::centred(10, 6, 4, 2);
+        assert_eq!((-3, -2), (viewport.x, viewport.y));
+        assert_eq!(None, viewport.to_grid(2, 2));
+        assert_eq!(Some((0, 0)), viewport.to_grid(3, 2));
+        assert_eq!(None, viewport.to_grid(10, 2));
+    }
+
+    #[test]
+    fn manual_test_centred_large_grid_crops() {
+        let viewport = Viewport::centred(4, 2, 10, 7);
+        assert_eq!((3, 2), (viewport.x, viewport.y));
+        assert_eq!(Some((3, 2)), viewport.to_grid(0, 0));
+        assert_eq!(Some((6, 3)), viewport.to_grid(3, 1));
+        assert_eq!(None, viewport.to_grid(0, 2));
+    }
+
+    #[test]
+    fn manual_test_pan_stops_at_edges() {
+        let mut viewport = Viewport::centred(4, 2, 10, 7);
+        viewport.pan(-100, 1, 10, 7);
+        assert_eq!((0, 3), (viewport.x, viewport.y));
+        viewport.pan(100, 100, 10, 7);
+        assert_eq!((6, 5), (viewport.x, viewport.y));
+
+        // Content that fits stays centred whatever the pan.
+        viewport.pan(5, 5, 2, 2);
+        assert_eq!((-1, 0), (viewport.x, viewport.y));
+    }
+
+    #[test]
+    fn manual_test_follow() {
+        let mut viewport = Viewport::centred(8, 4, 100, 100);
+        viewport.centre_on(50, 50, 100, 100);
+        assert!(viewport.is_near_centre(50, 50));
+        assert!(!viewport.is_near_centre(46, 50));
+
+        viewport.centre_on(99, 0, 100, 100);
+        assert_eq!((92, 0), (viewport.x, viewport.y));
+    }
+
+    #[test]
+    fn manual_test_step_zoom() {
+        let mut viewport = Viewport::centred(8, 4, 8, 4);
+        assert_eq!(None, viewport.step_zoom(false));
+        assert_eq!(Some(2), viewport.step_zoom(true));
+        viewport.zoom = 32;
+        assert_eq!(None, viewport.step_zoom(true));
+        assert_eq!(Some(16), viewport.step_zoom(false));
+    }
+
+    #[test]
+    fn manual_test_sample_blocks() {
+        // # # . .
+        // # . . o
+        // . . . .
+        let mut grid = Grid::new(4, 3);
+        for point in [Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)] {
+            *grid.get_mut(point).unwrap() = Space::Obstacle;
+        }
+        *grid.get_mut(Point::new(3, 1)).unwrap() = Space::Visited(4);
+
+        assert_eq!(Some(Space::Obstacle), sample(&grid, 1, 0, 0));
+        assert_eq!(Some(Space::Obstacle), sample(&grid, 2, 0, 0));
+        assert_eq!(Some(Space::Visited(4)), sample(&grid, 2, 1, 0));
+        // The last row of blocks is cut short by the edge of the grid.
+        assert_eq!(Some(Space::Empty), sample(&grid, 2, 0, 1));
+        assert_eq!(None, sample(&grid, 2, 2, 0));
+        assert_eq!(Some(Space::Visited(4)), sample(&grid, 4, 0, 0));
+    }
+}
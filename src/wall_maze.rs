@@ -0,0 +1,374 @@
+use std::fmt;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{grid::Grid, point::Point, space::Space};
+
+/// The walls around a single cell, one bit per side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Walls(u8);
+
+impl Walls {
+    pub const NONE: Walls = Walls(0);
+    pub const NORTH: Walls = Walls(1);
+    pub const EAST: Walls = Walls(2);
+    pub const SOUTH: Walls = Walls(4);
+    pub const WEST: Walls = Walls(8);
+    pub const ALL: Walls = Walls(15);
+
+    pub fn contains(&self, other: Walls) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Walls) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Walls) {
+        self.0 &= !other.0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn wall(&self) -> Walls {
+        match self {
+            Direction::North => Walls::NORTH,
+            Direction::East => Walls::EAST,
+            Direction::South => Walls::SOUTH,
+            Direction::West => Walls::WEST,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+}
+
+/// A maze of cells separated by thin walls, as opposed to [`Grid`] where a wall
+/// takes up a whole cell. Each cell stores all four of its walls; the two sides
+/// of a shared wall are kept in sync by [`WallMaze::remove_wall`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WallMaze {
+    pub walls: Vec<Walls>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl WallMaze {
+    /// Characters used for the inside of a cell by [`WallMaze::box_lines`].
+    pub const CELL_WIDTH: usize = 3;
+
+    /// Every cell starts out closed on all four sides.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            walls: vec![Walls::ALL; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn get(&self, point: Point) -> Option<Walls> {
+        if point.in_bound(self.width, self.height) {
+            self.walls.get(point.index(self.width)).copied()
+        } else {
+            None
+        }
+    }
+
+    pub fn has_wall(&self, point: Point, direction: Direction) -> bool {
+        self.get(point)
+            .is_some_and(|walls| walls.contains(direction.wall()))
+    }
+
+    pub fn neighbour(&self, point: Point, direction: Direction) -> Option<Point> {
+        let (dx, dy) = direction.offset();
+        let x = point.x.checked_add_signed(dx)?;
+        let y = point.y.checked_add_signed(dy)?;
+        Point::new(x, y)
+            .in_bound(self.width, self.height)
+            .then_some(Point::new(x, y))
+    }
+
+    /// Knocks down the wall on `direction` side of `point`, and the matching
+    /// wall of the neighbour behind it. Outer walls can be removed too, e.g. to
+    /// make an entrance.
+    pub fn remove_wall(&mut self, point: Point, direction: Direction) {
+        if !point.in_bound(self.width, self.height) {
+            return;
+        }
+        self.walls[point.index(self.width)].remove(direction.wall());
+        if let Some(neighbour) = self.neighbour(point, direction) {
+            self.walls[neighbour.index(self.width)].remove(direction.opposite().wall());
+        }
+    }
+
+    /// Carves a perfect maze (exactly one route between any two cells) with a
+    /// randomised depth-first search seeded by `seed`.
+    pub fn carve(&mut self, seed: u64) {
+        if self.walls.is_empty() {
+            return;
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut seen = vec![false; self.walls.len()];
+        let mut stack = vec![Point::default()];
+        seen[0] = true;
+
+        while let Some(&current) = stack.last() {
+            let mut directions = Direction::ALL;
+            directions.shuffle(&mut rng);
+            let next = directions.into_iter().find_map(|direction| {
+                let neighbour = self.neighbour(current, direction)?;
+                (!seen[neighbour.index(self.width)]).then_some((direction, neighbour))
+            });
+
+            match next {
+                Some((direction, neighbour)) => {
+                    self.remove_wall(current, direction);
+                    seen[neighbour.index(self.width)] = true;
+                    stack.push(neighbour);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    /// Converts to a cell-based [`Grid`] of `2 * width + 1` by
+    /// `2 * height + 1`, where walls and wall junctions become obstacles. The
+    /// start is the top-left cell and the end the bottom-right one, so any
+    /// solver can run on it.
+    pub fn to_grid(&self) -> Grid {
+        let mut grid = Grid::new(self.width * 2 + 1, self.height * 2 + 1);
+        for space in grid.spaces.iter_mut() {
+            *space = Space::Obstacle;
+        }
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let point = Point::new(x, y);
+                let centre = Point::new(x * 2 + 1, y * 2 + 1);
+                *grid.get_mut(centre).unwrap() = Space::Empty;
+                if !self.has_wall(point, Direction::East) && x + 1 < self.width {
+                    *grid.get_mut(Point::new(centre.x + 1, centre.y)).unwrap() = Space::Empty;
+                }
+                if !self.has_wall(point, Direction::South) && y + 1 < self.height {
+                    *grid.get_mut(Point::new(centre.x, centre.y + 1)).unwrap() = Space::Empty;
+                }
+            }
+        }
+        if self.width > 0 && self.height > 0 {
+            let start = Point::new(1, 1);
+            let end = Point::new(self.width * 2 - 1, self.height * 2 - 1);
+            *grid.get_mut(start).unwrap() = Space::Start(start);
+            *grid.get_mut(end).unwrap() = Space::End(end);
+        }
+        grid
+    }
+
+    /// The cell shown at `point` in the grid from [`WallMaze::to_grid`], or
+    /// `None` if `point` is a wall or a gap between cells.
+    pub fn cell_of(point: Point) -> Option<Point> {
+        (point.x % 2 == 1 && point.y % 2 == 1).then(|| Point::new(point.x / 2, point.y / 2))
+    }
+
+    /// Whether the vertical wall line at lattice column `x` is solid in row `y`.
+    pub(crate) fn vertical_wall(&self, x: usize, y: usize) -> bool {
+        if x < self.width {
+            self.has_wall(Point::new(x, y), Direction::West)
+        } else {
+            self.has_wall(Point::new(self.width - 1, y), Direction::East)
+        }
+    }
+
+    /// Whether the horizontal wall line at lattice row `y` is solid in column
+    /// `x`.
+    pub(crate) fn horizontal_wall(&self, x: usize, y: usize) -> bool {
+        if y < self.height {
+            self.has_wall(Point::new(x, y), Direction::North)
+        } else {
+            self.has_wall(Point::new(x, self.height - 1), Direction::South)
+        }
+    }
+
+    /// The box-drawing glyph for the corner at lattice point `x`, `y`, picked
+    /// from which of the four wall segments meeting there are solid.
+    pub fn junction(&self, x: usize, y: usize) -> char {
+        let up = y > 0 && self.vertical_wall(x, y - 1);
+        let down = y < self.height && self.vertical_wall(x, y);
+        let left = x > 0 && self.horizontal_wall(x - 1, y);
+        let right = x < self.width && self.horizontal_wall(x, y);
+        match (up, down, left, right) {
+            (false, false, false, false) => ' ',
+            (_, _, false, false) => '│',
+            (false, false, _, _) => '─',
+            (false, true, false, true) => '┌',
+            (false, true, true, false) => '┐',
+            (true, false, false, true) => '└',
+            (true, false, true, false) => '┘',
+            (true, true, false, true) => '├',
+            (true, true, true, false) => '┤',
+            (false, true, true, true) => '┬',
+            (true, false, true, true) => '┴',
+            (true, true, true, true) => '┼',
+        }
+    }
+
+    /// Renders the maze with Unicode box-drawing characters, one string per
+    /// terminal row. Each cell is [`WallMaze::CELL_WIDTH`] characters wide and
+    /// one tall, with walls and junctions drawn between cells.
+    pub fn box_lines(&self) -> Vec<String> {
+        if self.width == 0 || self.height == 0 {
+            return vec![];
+        }
+        let mut lines = Vec::with_capacity(self.height * 2 + 1);
+        for y in 0..=self.height {
+            let mut line = String::new();
+            for x in 0..=self.width {
+                line.push(self.junction(x, y));
+                if x < self.width {
+                    let ch = if self.horizontal_wall(x, y) {
+                        '─'
+                    } else {
+                        ' '
+                    };
+                    line.extend(std::iter::repeat_n(ch, Self::CELL_WIDTH));
+                }
+            }
+            lines.push(line);
+
+            if y < self.height {
+                let mut line = String::new();
+                for x in 0..=self.width {
+                    line.push(if self.vertical_wall(x, y) { '│' } else { ' ' });
+                    if x < self.width {
+                        line.extend(std::iter::repeat_n(' ', Self::CELL_WIDTH));
+                    }
+                }
+                lines.push(line);
+            }
+        }
+        lines
+    }
+}
+
+impl fmt::Display for WallMaze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.box_lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod wall_maze_tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{Direction, WallMaze, Walls};
+    use crate::{
+        maze::{bfs, Progress},
+        point::Point,
+    };
+
+    #[test]
+    fn manual_test_remove_wall_both_sides() {
+        let mut maze = WallMaze::new(2, 1);
+        maze.remove_wall(Point::new(0, 0), Direction::East);
+
+        assert!(!maze.has_wall(Point::new(0, 0), Direction::East));
+        assert!(!maze.has_wall(Point::new(1, 0), Direction::West));
+        let walls = maze.get(Point::new(0, 0)).unwrap();
+        assert!(walls.contains(Walls::NORTH));
+        assert!(walls.contains(Walls::SOUTH));
+        assert!(walls.contains(Walls::WEST));
+    }
+
+    #[test]
+    fn manual_test_box_lines() {
+        let mut maze = WallMaze::new(2, 2);
+        maze.remove_wall(Point::new(0, 0), Direction::East);
+        maze.remove_wall(Point::new(1, 0), Direction::South);
+        maze.remove_wall(Point::new(1, 1), Direction::West);
+
+        let expected = "\
+┌───────┐
+│       │
+├────   │
+│       │
+└───────┘
+";
+        assert_eq!(expected, maze.to_string());
+    }
+
+    #[test]
+    fn manual_test_all_junctions() {
+        let maze = WallMaze::new(2, 2);
+
+        let expected = "\
+┌───┬───┐
+│   │   │
+├───┼───┤
+│   │   │
+└───┴───┘
+";
+        assert_eq!(expected, maze.to_string());
+    }
+
+    #[test]
+    fn fuzzy_test_carve_is_perfect() {
+        for seed in 0..50 {
+            let mut maze = WallMaze::new(7, 5);
+            maze.carve(seed);
+
+            // A spanning tree over n cells opens exactly n - 1 inner walls.
+            let open: usize = maze
+                .walls
+                .iter()
+                .map(|walls| 4 - (0..4).filter(|i| walls.contains(Walls(1 << i))).count())
+                .sum();
+            assert_eq!(2 * (7 * 5 - 1), open);
+
+            let grid = maze.to_grid();
+            let start = grid.start().unwrap();
+            let solution = bfs(
+                start,
+                Arc::new(Mutex::new(grid)),
+                Duration::ZERO,
+                &Progress::default(),
+            );
+            assert_eq!(Some(&Point::new(13, 9)), solution.path.last());
+        }
+    }
+}